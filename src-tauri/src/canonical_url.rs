@@ -0,0 +1,117 @@
+use url::Url;
+
+// 常见的跟踪参数，不影响页面内容
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid", "_hsenc", "_hsmi",
+];
+
+fn is_tracking_param(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.starts_with("utm_") || TRACKING_PARAMS.contains(&name.as_str())
+}
+
+/// 计算用于判重的规范化 URL：
+/// - scheme 和主机名转小写，去掉默认端口
+/// - 去掉 utm_* / fbclid 等跟踪参数，其余参数按名称排序并重新编码，
+///   没有值的参数也会加上 =（`?flag` 变成 `?flag=`）
+/// - 去掉路径末尾的 /（根路径除外）
+/// - 去掉锚点，但保留 #/ 和 #! 形式的前端路由
+///
+/// 无法解析的 URL 原样返回（去掉首尾空白）
+pub fn canonicalize(input: &str) -> String {
+    let input = input.trim();
+    let mut url = match Url::parse(input) {
+        Ok(url) => url,
+        Err(_) => return input.to_string(),
+    };
+
+    // 非层级 URL（mailto:、javascript: 等）没有可规范化的部分
+    if url.cannot_be_a_base() {
+        return url.to_string();
+    }
+
+    let mut pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| !is_tracking_param(name))
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    pairs.sort();
+    if pairs.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(&pairs);
+    }
+
+    let keep_fragment = url
+        .fragment()
+        .is_some_and(|f| f.starts_with('/') || f.starts_with('!'));
+    if !keep_fragment {
+        url.set_fragment(None);
+    }
+
+    let path = url.path().to_string();
+    if path.len() > 1 && path.ends_with('/') {
+        url.set_path(path.trim_end_matches('/'));
+    }
+
+    url.to_string()
+}
+
+/// 网址的主机名（小写），用于 site: 搜索。无法解析或没有主机名时返回 None
+pub fn host(input: &str) -> Option<String> {
+    let url = Url::parse(input.trim()).ok()?;
+    let host = url.host_str()?.trim_end_matches('.');
+    (!host.is_empty()).then(|| host.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonicalize_cases() {
+        let cases = [
+            // 大小写和默认端口
+            ("HTTPS://Example.COM:443/Path", "https://example.com/Path"),
+            ("http://example.com:80/", "http://example.com/"),
+            ("http://example.com:8080/", "http://example.com:8080/"),
+            // 跟踪参数
+            ("https://example.com/?utm_source=feed&UTM_Medium=x", "https://example.com/"),
+            ("https://example.com/a?id=1&fbclid=abc", "https://example.com/a?id=1"),
+            // 参数排序，没有值的参数加上 =
+            ("https://example.com/s?q=rust&a=1&a=0", "https://example.com/s?a=0&a=1&q=rust"),
+            ("https://example.com/?flag", "https://example.com/?flag="),
+            // 路径末尾的 /
+            ("https://example.com/docs/", "https://example.com/docs"),
+            ("https://example.com/docs//", "https://example.com/docs"),
+            ("https://example.com", "https://example.com/"),
+            // 锚点
+            ("https://example.com/page#section", "https://example.com/page"),
+            ("https://example.com/app#/inbox", "https://example.com/app#/inbox"),
+            ("https://example.com/app#!/inbox", "https://example.com/app#!/inbox"),
+            // 非层级和无法解析的 URL
+            ("mailto:Someone@Example.com?subject=Hi", "mailto:Someone@Example.com?subject=Hi"),
+            ("javascript:void(0)", "javascript:void(0)"),
+            ("  not a url  ", "not a url"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(canonicalize(input), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn host_cases() {
+        let cases = [
+            ("https://Doc.Rust-Lang.org/std/", Some("doc.rust-lang.org")),
+            ("http://example.com.:8080/a", Some("example.com")),
+            ("https://user:pw@sub.example.com/", Some("sub.example.com")),
+            ("http://127.0.0.1:3000/", Some("127.0.0.1")),
+            ("mailto:someone@example.com", None),
+            ("file:///home/user/a.html", None),
+            ("not a url", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(host(input).as_deref(), expected, "{}", input);
+        }
+    }
+}
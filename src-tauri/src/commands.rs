@@ -1,8 +1,20 @@
 use crate::database::Database;
-use crate::models::{Bookmark, CreateBookmarkInput, UpdateBookmarkInput};
-use crate::import::{parse_html_bookmarks};
-use tauri::State;
+use crate::export::{self, SnapshotFolder};
+use crate::models::{
+    Bookmark, CreateBookmarkInput, CsvColumn, DeleteFolderMode, DuplicateGroup, ExportFormat,
+    Folder, ImportFormat, ImportPolicy, ImportProgress, ImportReport, SavedSearch, SavedSearchInput, SearchResult, Settings, Tag, UpdateBookmarkInput,
+};
+use crate::import;
+use crate::settings;
+use tauri::{AppHandle, Emitter, Manager, State};
 use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// 正在进行的导入的取消标记
+#[derive(Clone, Default)]
+pub struct ImportCancel(Arc<AtomicBool>);
 
 #[tauri::command]
 pub async fn create_bookmark(
@@ -45,10 +57,128 @@ pub async fn delete_bookmark(db: State<'_, Database>, id: i64) -> Result<(), Str
 pub async fn search_bookmarks(
     db: State<'_, Database>,
     query: String,
-) -> Result<Vec<Bookmark>, String> {
+) -> Result<Vec<SearchResult>, String> {
     db.search_bookmarks(&query).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn find_duplicates(db: State<'_, Database>) -> Result<Vec<DuplicateGroup>, String> {
+    db.find_duplicates().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn merge_bookmarks(
+    db: State<'_, Database>,
+    keep_id: i64,
+    merge_ids: Vec<i64>,
+) -> Result<Bookmark, String> {
+    db.merge_bookmarks(keep_id, &merge_ids).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_tags(db: State<'_, Database>) -> Result<Vec<Tag>, String> {
+    db.list_tags().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rename_tag(
+    db: State<'_, Database>,
+    old_name: String,
+    new_name: String,
+) -> Result<(), String> {
+    db.rename_tag(&old_name, &new_name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn merge_tags(
+    db: State<'_, Database>,
+    source: String,
+    target: String,
+) -> Result<(), String> {
+    db.merge_tags(&source, &target).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_tag(db: State<'_, Database>, name: String) -> Result<(), String> {
+    db.delete_tag(&name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_folders(db: State<'_, Database>) -> Result<Vec<Folder>, String> {
+    db.list_folders().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_folder(
+    db: State<'_, Database>,
+    name: String,
+    parent_id: Option<i64>,
+) -> Result<Folder, String> {
+    db.create_folder(&name, parent_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rename_folder(
+    db: State<'_, Database>,
+    id: i64,
+    name: String,
+) -> Result<Folder, String> {
+    db.rename_folder(id, &name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn move_folder(
+    db: State<'_, Database>,
+    id: i64,
+    parent_id: Option<i64>,
+) -> Result<Folder, String> {
+    db.move_folder(id, parent_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_folder(
+    db: State<'_, Database>,
+    id: i64,
+    mode: DeleteFolderMode,
+) -> Result<(), String> {
+    db.delete_folder(id, mode).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_saved_searches(db: State<'_, Database>) -> Result<Vec<SavedSearch>, String> {
+    db.list_saved_searches().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_saved_search(
+    db: State<'_, Database>,
+    input: SavedSearchInput,
+) -> Result<SavedSearch, String> {
+    db.create_saved_search(input).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_saved_search(
+    db: State<'_, Database>,
+    id: i64,
+    input: SavedSearchInput,
+) -> Result<SavedSearch, String> {
+    db.update_saved_search(id, input).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_saved_search(db: State<'_, Database>, id: i64) -> Result<(), String> {
+    db.delete_saved_search(id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn evaluate_saved_search(
+    db: State<'_, Database>,
+    id: i64,
+) -> Result<Vec<Bookmark>, String> {
+    db.evaluate_saved_search(id).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_bookmarks_with_reminders(
     db: State<'_, Database>,
@@ -72,100 +202,118 @@ pub async fn record_visit(
 
 #[tauri::command]
 pub async fn import_bookmarks(
+    app: AppHandle,
     db: State<'_, Database>,
+    cancel: State<'_, ImportCancel>,
     file_path: String,
-) -> Result<usize, String> {
-    let imported = parse_html_bookmarks(&file_path).map_err(|e| e.to_string())?;
-
-    let mut count = 0;
-    for bookmark in imported {
-        let input = CreateBookmarkInput {
-            title: bookmark.title,
-            url: bookmark.url,
-            category: bookmark.folder,
-            tags: vec![],
-            notes: None,
-            reminder: None,
-        };
-
-        if db.create_bookmark(input).is_ok() {
-            count += 1;
-        }
-    }
+    format: Option<ImportFormat>,
+    policy: Option<ImportPolicy>,
+    dry_run: Option<bool>,
+) -> Result<ImportReport, String> {
+    // 没有指定格式时根据文件内容判断
+    let format = match format {
+        Some(format) => format,
+        None => import::detect_format(&file_path).map_err(|e| e.to_string())?,
+    };
+    let db = db.inner().clone();
+    let cancel = cancel.inner().0.clone();
+    cancel.store(false, Ordering::Relaxed);
 
-    Ok(count)
+    // 大文件导入耗时较长，放到阻塞线程中执行，每个批次完成后发送 import-progress 事件
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let source = import::open_source(&file_path, format)?;
+        let (total, bytes_read, total_bytes) = (source.total, source.bytes_read, source.total_bytes);
+
+        db.import_stream(source.items, policy.unwrap_or_default(), dry_run.unwrap_or(false), |report| {
+            let progress = ImportProgress {
+                processed: report.created + report.updated + report.skipped + report.failed.len(),
+                created: report.created,
+                updated: report.updated,
+                skipped: report.skipped,
+                failed: report.failed.len(),
+                total,
+                bytes_read: bytes_read.load(Ordering::Relaxed),
+                total_bytes,
+            };
+            let _ = app.emit("import-progress", &progress);
+            !cancel.load(Ordering::Relaxed)
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    result.map_err(|e| e.to_string())
+}
+
+/// 取消正在进行的导入，当前批次完成后停止
+#[tauri::command]
+pub async fn cancel_import(cancel: State<'_, ImportCancel>) -> Result<(), String> {
+    cancel.inner().0.store(true, Ordering::Relaxed);
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn export_bookmarks(
     db: State<'_, Database>,
     file_path: String,
+    format: Option<ExportFormat>,
+    columns: Option<Vec<CsvColumn>>,
+    include_saved_searches: Option<bool>,
 ) -> Result<usize, String> {
     let bookmarks = db.get_all_bookmarks().map_err(|e| e.to_string())?;
+    // 没有指定的选项使用设置中的默认值
+    let defaults = db.get_settings().map_err(|e| e.to_string())?;
 
-    let mut html = String::from(
-        r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
-<!-- This is an automatically generated file.
-     It will be read and overwritten.
-     DO NOT EDIT! -->
-<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
-<TITLE>Bookmarks</TITLE>
-<H1>Bookmarks</H1>
-<DL><p>
-"#
-    );
-
-    // 按分类分组
-    let mut categories: std::collections::HashMap<String, Vec<&Bookmark>> = std::collections::HashMap::new();
-    let mut uncategorized: Vec<&Bookmark> = Vec::new();
-
-    for bookmark in &bookmarks {
-        if let Some(category) = &bookmark.category {
-            categories.entry(category.clone()).or_insert_with(Vec::new).push(bookmark);
-        } else {
-            uncategorized.push(bookmark);
+    let contents = match format.unwrap_or(defaults.export_format) {
+        ExportFormat::Html => {
+            let folders = db.list_folders().map_err(|e| e.to_string())?;
+            // 智能文件夹按导出时的结果生成快照
+            let mut snapshots = Vec::new();
+            if include_saved_searches.unwrap_or(defaults.export_include_saved_searches) {
+                for search in db.list_saved_searches().map_err(|e| e.to_string())? {
+                    snapshots.push(SnapshotFolder {
+                        bookmarks: db.evaluate_saved_search(search.id).map_err(|e| e.to_string())?,
+                        name: search.name,
+                    });
+                }
+            }
+            export::write_netscape_html(&folders, &bookmarks, &snapshots)
         }
-    }
-
-    // 导出分类书签
-    for (category, bookmarks) in categories.iter() {
-        html.push_str(&format!("    <DT><H3>{}</H3>\n", category));
-        html.push_str("    <DL><p>\n");
-        for bookmark in bookmarks {
-            let tags = if !bookmark.tags.is_empty() {
-                format!(" TAGS=\"{}\"", bookmark.tags.join(","))
-            } else {
-                String::new()
-            };
-            html.push_str(&format!(
-                "        <DT><A HREF=\"{}\"{}>{}</A>\n",
-                bookmark.url, tags, bookmark.title
-            ));
+        ExportFormat::Json => export::write_json(&bookmarks).map_err(|e| e.to_string())?,
+        ExportFormat::Csv => {
+            let columns = columns.filter(|c| !c.is_empty()).unwrap_or(defaults.export_columns);
+            export::write_csv(&bookmarks, &columns).map_err(|e| e.to_string())?
         }
-        html.push_str("    </DL><p>\n");
-    }
+    };
+    fs::write(&file_path, contents).map_err(|e| e.to_string())?;
 
-    // 导出未分类书签
-    if !uncategorized.is_empty() {
-        html.push_str("    <DT><H3>未分类</H3>\n");
-        html.push_str("    <DL><p>\n");
-        for bookmark in uncategorized {
-            let tags = if !bookmark.tags.is_empty() {
-                format!(" TAGS=\"{}\"", bookmark.tags.join(","))
-            } else {
-                String::new()
-            };
-            html.push_str(&format!(
-                "        <DT><A HREF=\"{}\"{}>{}</A>\n",
-                bookmark.url, tags, bookmark.title
-            ));
-        }
-        html.push_str("    </DL><p>\n");
-    }
+    Ok(bookmarks.len())
+}
 
-    html.push_str("</DL><p>\n");
+#[tauri::command]
+pub async fn get_settings(app: AppHandle, db: State<'_, Database>) -> Result<Settings, String> {
+    let mut settings = db.get_settings().map_err(|e| e.to_string())?;
+    settings.database_path = settings::configured_database_location(&app_data_dir(&app)?);
+    Ok(settings)
+}
 
-    fs::write(&file_path, html).map_err(|e| e.to_string())?;
+/// 保存设置并向前端发送 settings-changed 事件。
+///
+/// 数据库位置在下次启动时才更换，启动时新位置还没有数据库文件则把当前的数据库复制过去
+#[tauri::command]
+pub async fn update_settings(
+    app: AppHandle,
+    db: State<'_, Database>,
+    settings: Settings,
+) -> Result<Settings, String> {
+    db.update_settings(&settings).map_err(|e| e.to_string())?;
+    settings::set_database_location(&app_data_dir(&app)?, settings.database_path.as_deref())
+        .map_err(|e| e.to_string())?;
 
-    Ok(bookmarks.len())
+    let _ = app.emit("settings-changed", &settings);
+    Ok(settings)
+}
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path().app_data_dir().map_err(|e| e.to_string())
 }
@@ -1,119 +1,204 @@
-use anyhow::Result;
-use rusqlite::{params, Connection};
+use anyhow::{anyhow, bail, Context, Result};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Transaction};
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
-use crate::models::{Bookmark, CreateBookmarkInput, UpdateBookmarkInput};
+use tokio::sync::Notify;
+use crate::canonical_url;
+use crate::import::ImportedBookmark;
+use crate::migrations;
+use crate::query::{self, Comparison, Expr, Term};
+use crate::schedule;
+use crate::settings;
+use crate::models::{
+    Bookmark, CatchUpPolicy, CreateBookmarkInput, DeleteFolderMode, DuplicateGroup, Folder,
+    ImportFailure, ImportPolicy, ImportReport, Reminder, ReminderEvent,
+    ReminderEventKind, ReminderStats, SavedSearch, SavedSearchInput, SearchResult, Settings, Tag,
+    UpdateBookmarkInput,
+};
+
+// 与 row_to_bookmark 的列顺序一致，tags 和 reminders 由关联表聚合为 JSON 数组
+const BOOKMARK_COLUMNS: &str = "bookmarks.id, bookmarks.title, bookmarks.url, bookmarks.folder_id,
+    (SELECT path FROM folder_paths WHERE folder_paths.id = bookmarks.folder_id) AS folder_path,
+    (SELECT json_group_array(name) FROM (
+        SELECT t.name FROM bookmark_tags bt JOIN tags t ON t.id = bt.tag_id
+        WHERE bt.bookmark_id = bookmarks.id ORDER BY t.name
+    )) AS tags,
+    bookmarks.icon_url, bookmarks.notes,
+    (SELECT json_group_array(json(reminder)) FROM (
+        SELECT json_object(
+            'id', r.id, 'enabled', json(CASE WHEN r.enabled THEN 'true' ELSE 'false' END),
+            'frequency', json(r.frequency), 'time', r.time, 'days', json(r.days),
+            'catch_up', r.catch_up, 'timezone', r.timezone,
+            'title_template', r.title_template, 'message_template', r.message_template,
+            'last_reminded', r.last_reminded, 'next_reminder', r.next_reminder
+        ) AS reminder
+        FROM reminders r WHERE r.bookmark_id = bookmarks.id ORDER BY r.time, r.id
+    )) AS reminders,
+    bookmarks.visit_count, bookmarks.last_visited, bookmarks.created_at, bookmarks.updated_at,
+    bookmarks.keyword, bookmarks.exclude_from_digest";
+
+// BOOKMARK_COLUMNS 的列数，查询在它后面追加的列从这个下标开始
+const BOOKMARK_COLUMN_COUNT: usize = 15;
+
+// 与 row_to_reminder 的列顺序一致
+const REMINDER_COLUMNS: &str = "id, enabled, frequency, time, days, catch_up, timezone,
+    title_template, message_template, last_reminded, next_reminder";
+
+// settings 表中应用设置的键
+const SETTINGS_KEY: &str = "settings";
+
+// 流式导入时每个事务处理的书签数
+const IMPORT_BATCH_SIZE: usize = 500;
+
+// 标题权重最高，其次是标签和文件夹（对应 bookmarks_fts 的列顺序）
+const SEARCH_WEIGHTS: &str = "10.0, 2.0, 4.0, 1.0, 5.0";
+
+const FOLDER_SELECT: &str = "SELECT f.id, f.name, f.parent_id, p.path,
+        (SELECT COUNT(*) FROM bookmarks b WHERE b.folder_id = f.id),
+        f.created_at
+    FROM folders f JOIN folder_paths p ON p.id = f.id";
 
 #[derive(Clone)]
 pub struct Database {
     conn: Arc<Mutex<Connection>>,
+    // 提醒设置变化时唤醒提醒服务重新计算休眠时间
+    reminder_changes: Arc<Notify>,
 }
 
 impl Database {
     pub fn new(db_path: &str) -> Result<Self> {
         let conn = Connection::open(db_path)?;
+        conn.pragma_update(None, "foreign_keys", true)?;
         let db = Database {
             conn: Arc::new(Mutex::new(conn)),
+            reminder_changes: Arc::new(Notify::new()),
         };
         db.init_tables()?;
         Ok(db)
     }
 
     fn row_to_bookmark(row: &rusqlite::Row) -> rusqlite::Result<Bookmark> {
-        let tags_json: String = row.get(4)?;
+        let tags_json: String = row.get(5)?;
         let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
 
-        let reminder_json: Option<String> = row.get(7)?;
-        let reminder = reminder_json.and_then(|json| serde_json::from_str(&json).ok());
+        // 和 row_to_reminder 一样报错，不能把解析不了的提醒当成没有提醒，否则保存书签时会删掉它们
+        let reminders_json: String = row.get(8)?;
+        let reminders: Vec<Reminder> = serde_json::from_str(&reminders_json).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(8, rusqlite::types::Type::Text, e.into())
+        })?;
 
         Ok(Bookmark {
             id: Some(row.get(0)?),
             title: row.get(1)?,
             url: row.get(2)?,
-            category: row.get(3)?,
+            folder_id: row.get(3)?,
+            folder_path: row.get(4)?,
             tags,
-            icon_url: row.get(5)?,
-            notes: row.get(6)?,
-            reminder,
-            visit_count: row.get(8).unwrap_or(0),
-            last_visited: row.get(9)?,
-            created_at: row.get(10)?,
-            updated_at: row.get(11)?,
+            icon_url: row.get(6)?,
+            notes: row.get(7)?,
+            keyword: row.get(13)?,
+            reminders,
+            exclude_from_digest: row.get(14)?,
+            visit_count: row.get(9).unwrap_or(0),
+            last_visited: row.get(10)?,
+            created_at: row.get(11)?,
+            updated_at: row.get(12)?,
         })
     }
 
-    fn init_tables(&self) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+    fn row_to_reminder(row: &rusqlite::Row) -> rusqlite::Result<Reminder> {
+        let frequency: String = row.get(2)?;
+        let days: String = row.get(4)?;
+        let catch_up: String = row.get(5)?;
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS bookmarks (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                title TEXT NOT NULL,
-                url TEXT NOT NULL,
-                category TEXT,
-                tags TEXT,
-                icon_url TEXT,
-                notes TEXT,
-                reminder TEXT,
-                visit_count INTEGER DEFAULT 0,
-                last_visited TEXT,
-                created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL
-            )",
-            [],
-        )?;
-
-        // 添加新字段（如果表已存在）
-        let _ = conn.execute("ALTER TABLE bookmarks ADD COLUMN visit_count INTEGER DEFAULT 0", []);
-        let _ = conn.execute("ALTER TABLE bookmarks ADD COLUMN last_visited TEXT", []);
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_bookmarks_category ON bookmarks(category)",
-            [],
-        )?;
+        Ok(Reminder {
+            id: Some(row.get(0)?),
+            enabled: row.get(1)?,
+            frequency: serde_json::from_str(&frequency).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, e.into())
+            })?,
+            time: row.get(3)?,
+            days: serde_json::from_str(&days).unwrap_or_default(),
+            catch_up: CatchUpPolicy::from_name(&catch_up).unwrap_or_default(),
+            timezone: row.get(6)?,
+            title_template: row.get(7)?,
+            message_template: row.get(8)?,
+            last_reminded: row.get(9)?,
+            next_reminder: row.get(10)?,
+        })
+    }
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_bookmarks_created_at ON bookmarks(created_at)",
-            [],
-        )?;
+    fn init_tables(&self) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        migrations::migrate(&mut conn)
+    }
 
-        Ok(())
+    /// 书签的提醒被创建、修改或删除时会收到通知
+    pub fn reminder_changes(&self) -> Arc<Notify> {
+        self.reminder_changes.clone()
     }
 
     pub fn create_bookmark(&self, input: CreateBookmarkInput) -> Result<Bookmark> {
-        let conn = self.conn.lock().unwrap();
+        for reminder in &input.reminders {
+            schedule::validate(reminder)?;
+        }
+        let mut conn = self.conn.lock().unwrap();
         let now = chrono::Utc::now().to_rfc3339();
 
-        let tags_json = serde_json::to_string(&input.tags)?;
-        let reminder_json = input.reminder.as_ref().map(|r| serde_json::to_string(r).ok()).flatten();
+        let tx = conn.transaction()?;
+        let id = Self::insert_bookmark(&tx, &input, &now, &now)?;
+        tx.commit()?;
+
+        drop(conn); // 释放锁！
+        if !input.reminders.is_empty() {
+            self.reminder_changes.notify_one();
+        }
+        self.get_bookmark(id)
+    }
 
+    fn insert_bookmark(
+        conn: &Connection,
+        input: &CreateBookmarkInput,
+        created_at: &str,
+        updated_at: &str,
+    ) -> Result<i64> {
         conn.execute(
-            "INSERT INTO bookmarks (title, url, category, tags, notes, reminder, visit_count, last_visited, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            "INSERT INTO bookmarks (title, url, canonical_url, folder_id, icon_url, notes, keyword, visit_count, last_visited, created_at, updated_at, exclude_from_digest, host)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
             params![
                 input.title,
                 input.url,
-                input.category,
-                tags_json,
+                canonical_url::canonicalize(&input.url),
+                input.folder_id,
+                input.icon_url,
                 input.notes,
-                reminder_json,
+                input.keyword,
                 0,
                 None::<String>,
-                now,
-                now,
+                created_at,
+                updated_at,
+                input.exclude_from_digest,
+                canonical_url::host(&input.url),
             ],
         )?;
 
         let id = conn.last_insert_rowid();
-        drop(conn); // 释放锁！
-        self.get_bookmark(id)
+        Self::set_bookmark_tags(conn, id, &input.tags)?;
+        for reminder in &input.reminders {
+            let mut reminder = Reminder { id: None, ..reminder.clone() };
+            schedule::reschedule(None, &mut reminder, chrono::Utc::now());
+            Self::insert_reminder(conn, id, &reminder)?;
+        }
+        Ok(id)
     }
 
     pub fn get_bookmark(&self, id: i64) -> Result<Bookmark> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT id, title, url, category, tags, icon_url, notes, reminder, visit_count, last_visited, created_at, updated_at
-             FROM bookmarks WHERE id = ?1"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM bookmarks WHERE id = ?1",
+            BOOKMARK_COLUMNS
+        ))?;
 
         let bookmark = stmt.query_row(params![id], Self::row_to_bookmark)?;
         Ok(bookmark)
@@ -121,10 +206,10 @@ impl Database {
 
     pub fn get_all_bookmarks(&self) -> Result<Vec<Bookmark>> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT id, title, url, category, tags, icon_url, notes, reminder, visit_count, last_visited, created_at, updated_at
-             FROM bookmarks ORDER BY created_at DESC"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM bookmarks ORDER BY created_at DESC",
+            BOOKMARK_COLUMNS
+        ))?;
 
         let bookmarks = stmt.query_map([], Self::row_to_bookmark)?
             .collect::<Result<Vec<_>, _>>()?;
@@ -133,71 +218,505 @@ impl Database {
     }
 
     pub fn update_bookmark(&self, input: UpdateBookmarkInput) -> Result<Bookmark> {
-        let conn = self.conn.lock().unwrap();
+        for reminder in input.reminders.iter().flatten() {
+            schedule::validate(reminder)?;
+        }
+        let mut conn = self.conn.lock().unwrap();
         let now = chrono::Utc::now().to_rfc3339();
 
-        // 简化：直接更新所有字段
-        let tags_json = serde_json::to_string(&input.tags.unwrap_or_default())?;
-        let reminder_json = input.reminder.as_ref().map(|r| serde_json::to_string(r).ok()).flatten();
+        let tx = conn.transaction()?;
 
-        conn.execute(
+        // 简化：直接更新所有字段（标签和提醒为 None 时保持不变）
+        tx.execute(
             "UPDATE bookmarks SET
                 title = COALESCE(?1, title),
                 url = COALESCE(?2, url),
-                category = ?3,
-                tags = ?4,
+                canonical_url = COALESCE(?3, canonical_url),
+                host = CASE WHEN ?2 IS NULL THEN host ELSE ?9 END,
+                folder_id = ?4,
                 notes = ?5,
-                reminder = ?6,
-                updated_at = ?7
-             WHERE id = ?8",
+                updated_at = ?6,
+                exclude_from_digest = COALESCE(?8, exclude_from_digest)
+             WHERE id = ?7",
             params![
                 input.title,
                 input.url,
-                input.category,
-                tags_json,
+                input.url.as_deref().map(canonical_url::canonicalize),
+                input.folder_id,
                 input.notes,
-                reminder_json,
                 now,
                 input.id,
+                input.exclude_from_digest,
+                input.url.as_deref().and_then(canonical_url::host),
             ],
         )?;
 
+        if let Some(tags) = &input.tags {
+            Self::set_bookmark_tags(&tx, input.id, tags)?;
+        }
+        if let Some(reminders) = &input.reminders {
+            Self::set_bookmark_reminders(&tx, input.id, reminders)?;
+        }
+        tx.commit()?;
+
         drop(conn);
+        if input.reminders.is_some() {
+            self.reminder_changes.notify_one();
+        }
         self.get_bookmark(input.id)
     }
 
     pub fn delete_bookmark(&self, id: i64) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute("DELETE FROM bookmarks WHERE id = ?1", params![id])?;
+        self.reminder_changes.notify_one();
+        Ok(())
+    }
+
+    fn ensure_bookmark_exists(conn: &Connection, id: i64) -> Result<()> {
+        conn.query_row("SELECT id FROM bookmarks WHERE id = ?1", params![id], |_| Ok(()))
+            .optional()?
+            .ok_or_else(|| anyhow!("书签不存在: {}", id))
+    }
+
+    fn insert_reminder(conn: &Connection, bookmark_id: i64, reminder: &Reminder) -> Result<i64> {
+        conn.execute(
+            "INSERT INTO reminders (bookmark_id, enabled, frequency, time, days, catch_up, timezone,
+                title_template, message_template, last_reminded, next_reminder, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                bookmark_id,
+                reminder.enabled,
+                serde_json::to_string(&reminder.frequency)?,
+                reminder.time,
+                serde_json::to_string(&reminder.days)?,
+                reminder.catch_up.as_str(),
+                reminder.timezone,
+                reminder.title_template,
+                reminder.message_template,
+                reminder.last_reminded,
+                reminder.next_reminder,
+                chrono::Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    fn write_reminder(conn: &Connection, id: i64, reminder: &Reminder) -> Result<()> {
+        conn.execute(
+            "UPDATE reminders SET
+                enabled = ?1, frequency = ?2, time = ?3, days = ?4, catch_up = ?5, timezone = ?6,
+                title_template = ?7, message_template = ?8, last_reminded = ?9, next_reminder = ?10
+             WHERE id = ?11",
+            params![
+                reminder.enabled,
+                serde_json::to_string(&reminder.frequency)?,
+                reminder.time,
+                serde_json::to_string(&reminder.days)?,
+                reminder.catch_up.as_str(),
+                reminder.timezone,
+                reminder.title_template,
+                reminder.message_template,
+                reminder.last_reminded,
+                reminder.next_reminder,
+                id,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn query_reminders(conn: &Connection, bookmark_id: i64) -> Result<Vec<Reminder>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM reminders WHERE bookmark_id = ?1 ORDER BY time, id",
+            REMINDER_COLUMNS
+        ))?;
+        let reminders = stmt.query_map(params![bookmark_id], Self::row_to_reminder)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(reminders)
+    }
+
+    fn query_reminder(conn: &Connection, id: i64) -> Result<Reminder> {
+        conn.query_row(
+            &format!("SELECT {} FROM reminders WHERE id = ?1", REMINDER_COLUMNS),
+            params![id],
+            Self::row_to_reminder,
+        )
+        .optional()?
+        .ok_or_else(|| anyhow!("提醒不存在: {}", id))
+    }
+
+    // 用给定列表替换书签的提醒：带有该书签已有 id 的沿用原来的提醒记录和历史，
+    // 其余的新建，不在列表中的删除
+    fn set_bookmark_reminders(conn: &Connection, bookmark_id: i64, reminders: &[Reminder]) -> Result<()> {
+        let old = Self::query_reminders(conn, bookmark_id)?;
+        let now = chrono::Utc::now();
+
+        for id in old.iter().filter_map(|r| r.id) {
+            if !reminders.iter().any(|r| r.id == Some(id)) {
+                conn.execute("DELETE FROM reminders WHERE id = ?1", params![id])?;
+            }
+        }
+        for reminder in reminders {
+            let mut reminder = reminder.clone();
+            match old.iter().find(|o| o.id.is_some() && o.id == reminder.id) {
+                Some(old) => {
+                    schedule::reschedule(Some(old), &mut reminder, now);
+                    Self::write_reminder(conn, old.id.unwrap_or_default(), &reminder)?;
+                }
+                None => {
+                    reminder.id = None;
+                    schedule::reschedule(None, &mut reminder, now);
+                    Self::insert_reminder(conn, bookmark_id, &reminder)?;
+                }
+            }
+        }
         Ok(())
     }
 
-    pub fn search_bookmarks(&self, query: &str) -> Result<Vec<Bookmark>> {
+    pub fn list_reminders(&self, bookmark_id: i64) -> Result<Vec<Reminder>> {
         let conn = self.conn.lock().unwrap();
-        let search_pattern = format!("%{}%", query);
+        Self::ensure_bookmark_exists(&conn, bookmark_id)?;
+        Self::query_reminders(&conn, bookmark_id)
+    }
+
+    pub fn get_reminder(&self, id: i64) -> Result<Reminder> {
+        let conn = self.conn.lock().unwrap();
+        Self::query_reminder(&conn, id)
+    }
+
+    pub fn create_reminder(&self, bookmark_id: i64, reminder: Reminder) -> Result<Reminder> {
+        schedule::validate(&reminder)?;
+        let mut reminder = Reminder { id: None, ..reminder };
+        schedule::reschedule(None, &mut reminder, chrono::Utc::now());
+
+        let conn = self.conn.lock().unwrap();
+        Self::ensure_bookmark_exists(&conn, bookmark_id)?;
+        let id = Self::insert_reminder(&conn, bookmark_id, &reminder)?;
+        let reminder = Self::query_reminder(&conn, id)?;
+
+        drop(conn);
+        self.reminder_changes.notify_one();
+        Ok(reminder)
+    }
+
+    /// 修改提醒设置，沿用已有的提醒记录，时间设置变化时重新安排
+    pub fn update_reminder(&self, id: i64, reminder: Reminder) -> Result<Reminder> {
+        schedule::validate(&reminder)?;
+
+        let conn = self.conn.lock().unwrap();
+        let old = Self::query_reminder(&conn, id)?;
+        let mut reminder = Reminder { id: Some(id), ..reminder };
+        schedule::reschedule(Some(&old), &mut reminder, chrono::Utc::now());
+        Self::write_reminder(&conn, id, &reminder)?;
+        let reminder = Self::query_reminder(&conn, id)?;
+
+        drop(conn);
+        self.reminder_changes.notify_one();
+        Ok(reminder)
+    }
+
+    pub fn delete_reminder(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let deleted = conn.execute("DELETE FROM reminders WHERE id = ?1", params![id])?;
+        if deleted == 0 {
+            bail!("提醒不存在: {}", id);
+        }
+        drop(conn);
+        self.reminder_changes.notify_one();
+        Ok(())
+    }
 
+    /// 只更新提醒状态（触发、完成、稍后提醒），不改动提醒设置和书签的修改时间。
+    ///
+    /// 不唤醒提醒服务：服务自己保存状态时不需要重新计算，用户操作由调用方通知
+    pub fn save_reminder_state(&self, reminder: &Reminder) -> Result<()> {
+        let id = reminder.id.ok_or_else(|| anyhow!("提醒没有 id"))?;
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE reminders SET last_reminded = ?1, next_reminder = ?2 WHERE id = ?3",
+            params![reminder.last_reminded, reminder.next_reminder, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn add_reminder_event(
+        &self,
+        reminder_id: i64,
+        kind: ReminderEventKind,
+        scheduled_for: Option<&str>,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let inserted = conn.execute(
+            "INSERT INTO reminder_events (bookmark_id, reminder_id, kind, scheduled_for, created_at)
+             SELECT bookmark_id, id, ?2, ?3, ?4 FROM reminders WHERE id = ?1",
+            params![reminder_id, kind.as_str(), scheduled_for, at.to_rfc3339()],
+        )?;
+        if inserted == 0 {
+            bail!("提醒不存在: {}", reminder_id);
+        }
+        Ok(())
+    }
+
+    fn row_to_reminder_event(row: &rusqlite::Row) -> rusqlite::Result<ReminderEvent> {
+        let kind: String = row.get(4)?;
+
+        Ok(ReminderEvent {
+            id: row.get(0)?,
+            bookmark_id: row.get(1)?,
+            reminder_id: row.get(2)?,
+            bookmark_title: row.get(3)?,
+            kind: ReminderEventKind::from_name(&kind).ok_or_else(|| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    4,
+                    rusqlite::types::Type::Text,
+                    anyhow!("未知的提醒事件类型: {}", kind).into(),
+                )
+            })?,
+            scheduled_for: row.get(5)?,
+            created_at: row.get(6)?,
+        })
+    }
+
+    /// 提醒历史，最新的在前。不指定书签时返回所有书签的记录
+    pub fn list_reminder_events(&self, bookmark_id: Option<i64>, limit: usize) -> Result<Vec<ReminderEvent>> {
+        let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, title, url, category, tags, icon_url, notes, reminder, visit_count, last_visited, created_at, updated_at
-             FROM bookmarks
-             WHERE title LIKE ?1 OR url LIKE ?1 OR category LIKE ?1 OR notes LIKE ?1
-             ORDER BY created_at DESC"
+            "SELECT e.id, e.bookmark_id, e.reminder_id, b.title, e.kind, e.scheduled_for, e.created_at
+             FROM reminder_events e JOIN bookmarks b ON b.id = e.bookmark_id
+             WHERE ?1 IS NULL OR e.bookmark_id = ?1
+             ORDER BY e.created_at DESC, e.id DESC
+             LIMIT ?2",
         )?;
 
-        let bookmarks = stmt.query_map(params![search_pattern], Self::row_to_bookmark)?
+        let events = stmt.query_map(params![bookmark_id, limit as i64], Self::row_to_reminder_event)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(events)
+    }
+
+    /// 每个提醒的统计。指定书签时返回它的所有提醒（没有记录的为全零），
+    /// 不指定时返回每个有记录的提醒
+    pub fn reminder_stats(&self, bookmark_id: Option<i64>) -> Result<Vec<ReminderStats>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT reminder_id, bookmark_id, kind, created_at FROM reminder_events
+             WHERE reminder_id IS NOT NULL AND (?1 IS NULL OR bookmark_id = ?1)
+             ORDER BY reminder_id, created_at, id",
+        )?;
+        let rows = stmt
+            .query_map(params![bookmark_id], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stats: Vec<ReminderStats> = Vec::new();
+        let mut periods: Vec<Vec<bool>> = Vec::new();
+        for (id, bookmark, kind, created_at) in rows {
+            if stats.last().map(|s| s.reminder_id) != Some(id) {
+                stats.push(ReminderStats {
+                    reminder_id: id,
+                    bookmark_id: bookmark,
+                    ..Default::default()
+                });
+                periods.push(Vec::new());
+            }
+            let (entry, done) = (stats.last_mut().unwrap(), periods.last_mut().unwrap());
+
+            match ReminderEventKind::from_name(&kind) {
+                Some(ReminderEventKind::Fired) => {
+                    entry.fired += 1;
+                    done.push(false);
+                }
+                Some(ReminderEventKind::Completed) => {
+                    entry.completed += 1;
+                    entry.last_completed = Some(created_at);
+                    // 还没触发过就完成的也算一期
+                    match done.last_mut() {
+                        Some(last) => *last = true,
+                        None => done.push(true),
+                    }
+                }
+                Some(ReminderEventKind::Snoozed) => entry.snoozed += 1,
+                Some(ReminderEventKind::Dismissed) => entry.dismissed += 1,
+                // 跳过的不算一期，不影响连续完成
+                Some(ReminderEventKind::Skipped) => entry.skipped += 1,
+                None => {}
+            }
+        }
+
+        for (entry, mut done) in stats.iter_mut().zip(periods) {
+            // 最近一期还没完成时不计入
+            if done.last() == Some(&false) {
+                done.pop();
+            }
+            entry.current_streak = done.iter().rev().take_while(|d| **d).count();
+            entry.longest_streak = done
+                .split(|d| !*d)
+                .map(|run| run.len())
+                .max()
+                .unwrap_or(0);
+            if !done.is_empty() {
+                entry.completion_rate = done.iter().filter(|d| **d).count() as f64 / done.len() as f64;
+            }
+        }
+
+        if let Some(id) = bookmark_id {
+            // 确认书签存在，还没有记录的提醒返回全零的统计
+            drop(stmt);
+            Self::ensure_bookmark_exists(&conn, id)?;
+            for reminder_id in Self::query_reminders(&conn, id)?.iter().filter_map(|r| r.id) {
+                if !stats.iter().any(|s| s.reminder_id == reminder_id) {
+                    stats.push(ReminderStats {
+                        reminder_id,
+                        bookmark_id: id,
+                        ..Default::default()
+                    });
+                }
+            }
+            stats.sort_by_key(|s| s.reminder_id);
+        }
+
+        Ok(stats)
+    }
+
+    // 读取一项设置，没有保存过时返回默认值，无法解析时报错而不是悄悄丢掉原来的设置
+    fn get_setting<T: DeserializeOwned + Default>(&self, key: &str) -> Result<T> {
+        let conn = self.conn.lock().unwrap();
+        let json: Option<String> = conn
+            .query_row("SELECT value FROM settings WHERE key = ?1", params![key], |row| row.get(0))
+            .optional()?;
+        match json {
+            Some(json) => serde_json::from_str(&json).with_context(|| format!("设置 {} 已损坏", key)),
+            None => Ok(T::default()),
+        }
+    }
+
+    fn put_setting<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO settings (key, value, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+            params![key, serde_json::to_string(value)?, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// 读取应用设置。database_path 不在数据库中，由调用方从应用数据目录读取
+    pub fn get_settings(&self) -> Result<Settings> {
+        self.get_setting(SETTINGS_KEY)
+    }
+
+    /// 保存应用设置，提醒服务会立即按新的设置运行
+    pub fn update_settings(&self, new_settings: &Settings) -> Result<()> {
+        settings::validate(new_settings)?;
+        let stored = Settings { database_path: None, ..new_settings.clone() };
+        self.put_setting(SETTINGS_KEY, &stored)?;
+        self.reminder_changes.notify_one();
+        Ok(())
+    }
+
+    /// 把整个数据库复制到 path，用于更换数据库位置
+    pub fn copy_to(&self, path: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("VACUUM INTO ?1", params![path])?;
+        Ok(())
+    }
+
+    /// 搜索书签。纯关键词走全文索引并按 BM25 相关度排序（每个词按前缀匹配），
+    /// 包含字段过滤或布尔运算时编译为 SQL 条件，按创建时间排序。语法见 query 模块
+    pub fn search_bookmarks(&self, query: &str) -> Result<Vec<SearchResult>> {
+        let expr = match query::parse(query)? {
+            Some(expr) => expr,
+            None => return Ok(Vec::new()),
+        };
+
+        if let Some(words) = expr.plain_words() {
+            let results = self.search_full_text(&words)?;
+            // unicode61 不对中文分词，连续的中文会被当成一个词，没有结果时退回下面的子串匹配
+            if !results.is_empty() {
+                return Ok(results);
+            }
+        }
+
+        let results = self
+            .query_bookmarks(&expr)?
+            .into_iter()
+            .map(|bookmark| SearchResult {
+                bookmark,
+                snippet: None,
+                score: 0.0,
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    fn query_bookmarks(&self, expr: &Expr) -> Result<Vec<Bookmark>> {
+        let mut sql_params = Vec::new();
+        let condition = expr.to_sql(&mut sql_params);
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM bookmarks WHERE {} ORDER BY bookmarks.created_at DESC",
+            BOOKMARK_COLUMNS, condition
+        ))?;
+
+        let bookmarks = stmt.query_map(params_from_iter(sql_params), Self::row_to_bookmark)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(bookmarks)
     }
 
+    fn search_full_text(&self, words: &[&str]) -> Result<Vec<SearchResult>> {
+        let fts_query = Self::build_fts_query(words);
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {},
+                snippet(bookmarks_fts, -1, '<mark>', '</mark>', '…', 12) AS snippet,
+                bm25(bookmarks_fts, {}) AS score
+             FROM bookmarks_fts JOIN bookmarks ON bookmarks.id = bookmarks_fts.rowid
+             WHERE bookmarks_fts MATCH ?1
+             ORDER BY score",
+            BOOKMARK_COLUMNS, SEARCH_WEIGHTS
+        ))?;
+
+        let results = stmt.query_map(params![fts_query], |row| {
+            Ok(SearchResult {
+                bookmark: Self::row_to_bookmark(row)?,
+                snippet: row.get(BOOKMARK_COLUMN_COUNT)?,
+                score: row.get(BOOKMARK_COLUMN_COUNT + 1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(results)
+    }
+
+    // 将用户输入转换为 FTS5 查询：每个词加引号转义并做前缀匹配，词之间为 AND
+    fn build_fts_query(words: &[&str]) -> String {
+        words
+            .iter()
+            .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
     pub fn get_bookmarks_with_reminders(&self) -> Result<Vec<Bookmark>> {
         let conn = self.conn.lock().unwrap();
 
-        let mut stmt = conn.prepare(
-            "SELECT id, title, url, category, tags, icon_url, notes, reminder, visit_count, last_visited, created_at, updated_at
-             FROM bookmarks
-             WHERE reminder IS NOT NULL
-             ORDER BY created_at DESC"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM bookmarks
+             WHERE EXISTS (SELECT 1 FROM reminders WHERE reminders.bookmark_id = bookmarks.id)
+             ORDER BY created_at DESC",
+            BOOKMARK_COLUMNS
+        ))?;
 
         let bookmarks = stmt.query_map([], Self::row_to_bookmark)?
             .collect::<Result<Vec<_>, _>>()?;
@@ -216,4 +735,871 @@ impl Database {
 
         Ok(())
     }
+
+    // 用给定标签替换书签现有的标签，标签名忽略大小写去重
+    fn set_bookmark_tags(conn: &Connection, bookmark_id: i64, tags: &[String]) -> Result<()> {
+        conn.execute("DELETE FROM bookmark_tags WHERE bookmark_id = ?1", params![bookmark_id])?;
+
+        for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            let tag = Self::check_tag_name(tag)?;
+            conn.execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", params![tag])?;
+            conn.execute(
+                "INSERT OR IGNORE INTO bookmark_tags (bookmark_id, tag_id)
+                 SELECT ?1, id FROM tags WHERE name = ?2",
+                params![bookmark_id, tag],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // 导出的 HTML 用逗号分隔多个标签，名称中不能出现逗号
+    fn check_tag_name(name: &str) -> Result<&str> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("标签名不能为空");
+        }
+        if name.contains(',') {
+            bail!("标签名不能包含逗号: {}", name);
+        }
+        Ok(name)
+    }
+
+    fn find_tag_id(conn: &Connection, name: &str) -> Result<i64> {
+        conn.query_row("SELECT id FROM tags WHERE name = ?1", params![name.trim()], |row| row.get(0))
+            .optional()?
+            .ok_or_else(|| anyhow!("标签不存在: {}", name))
+    }
+
+    pub fn list_tags(&self) -> Result<Vec<Tag>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.name, COUNT(bt.bookmark_id)
+             FROM tags t LEFT JOIN bookmark_tags bt ON bt.tag_id = t.id
+             GROUP BY t.id
+             ORDER BY t.name"
+        )?;
+
+        let tags = stmt.query_map([], |row| {
+            Ok(Tag {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                bookmark_count: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(tags)
+    }
+
+    pub fn rename_tag(&self, old_name: &str, new_name: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let new_name = Self::check_tag_name(new_name)?;
+
+        let id = Self::find_tag_id(&conn, old_name)?;
+        if let Some(existing) = conn
+            .query_row("SELECT id FROM tags WHERE name = ?1", params![new_name], |row| row.get::<_, i64>(0))
+            .optional()?
+        {
+            // 只改大小写时仍是同一个标签
+            if existing != id {
+                bail!("标签已存在: {}，请使用合并", new_name);
+            }
+        }
+
+        conn.execute("UPDATE tags SET name = ?1 WHERE id = ?2", params![new_name, id])?;
+        Ok(())
+    }
+
+    /// 将 source 标签的所有书签归入 target，然后删除 source
+    pub fn merge_tags(&self, source: &str, target: &str) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let source_id = Self::find_tag_id(&tx, source)?;
+        let target_id = Self::find_tag_id(&tx, target)?;
+        if source_id == target_id {
+            return Ok(());
+        }
+
+        tx.execute(
+            "INSERT OR IGNORE INTO bookmark_tags (bookmark_id, tag_id)
+             SELECT bookmark_id, ?2 FROM bookmark_tags WHERE tag_id = ?1",
+            params![source_id, target_id],
+        )?;
+        tx.execute("DELETE FROM tags WHERE id = ?1", params![source_id])?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    pub fn delete_tag(&self, name: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let id = Self::find_tag_id(&conn, name)?;
+        // bookmark_tags 通过外键级联删除
+        conn.execute("DELETE FROM tags WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    fn row_to_folder(row: &rusqlite::Row) -> rusqlite::Result<Folder> {
+        Ok(Folder {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            parent_id: row.get(2)?,
+            path: row.get(3)?,
+            bookmark_count: row.get(4)?,
+            created_at: row.get(5)?,
+        })
+    }
+
+    fn query_folder(conn: &Connection, id: i64) -> Result<Folder> {
+        conn.query_row(
+            &format!("{} WHERE f.id = ?1", FOLDER_SELECT),
+            params![id],
+            Self::row_to_folder,
+        )
+        .optional()?
+        .ok_or_else(|| anyhow!("文件夹不存在: {}", id))
+    }
+
+    // 同一父文件夹下不允许重名
+    fn ensure_unique_folder_name(
+        conn: &Connection,
+        parent_id: Option<i64>,
+        name: &str,
+        exclude_id: Option<i64>,
+    ) -> Result<()> {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(
+                SELECT 1 FROM folders
+                WHERE COALESCE(parent_id, 0) = COALESCE(?1, 0) AND name = ?2 AND id != COALESCE(?3, 0)
+             )",
+            params![parent_id, name, exclude_id],
+            |row| row.get(0),
+        )?;
+        if exists {
+            bail!("同级目录下已存在文件夹: {}", name);
+        }
+        Ok(())
+    }
+
+    // 文件夹路径用 / 分隔各级，名称中不能出现 /
+    fn check_folder_name(name: &str) -> Result<&str> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("文件夹名不能为空");
+        }
+        if name.contains('/') {
+            bail!("文件夹名不能包含 /: {}", name);
+        }
+        Ok(name)
+    }
+
+    /// 按路径逐级查找文件夹，不存在的部分会被创建，空路径返回 None。
+    /// 导入的文件夹名中的 / 换成全角的 ／，不影响按路径查找
+    pub(crate) fn ensure_folder_path(conn: &Connection, path: &[String]) -> Result<Option<i64>> {
+        let now = chrono::Utc::now().to_rfc3339();
+        let mut parent_id: Option<i64> = None;
+
+        for name in path.iter().map(|n| n.trim()).filter(|n| !n.is_empty()) {
+            let name = name.replace('/', "／");
+            let existing: Option<i64> = conn
+                .query_row(
+                    "SELECT id FROM folders WHERE COALESCE(parent_id, 0) = COALESCE(?1, 0) AND name = ?2",
+                    params![parent_id, name],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            parent_id = Some(match existing {
+                Some(id) => id,
+                None => {
+                    conn.execute(
+                        "INSERT INTO folders (name, parent_id, created_at) VALUES (?1, ?2, ?3)",
+                        params![name, parent_id, now],
+                    )?;
+                    conn.last_insert_rowid()
+                }
+            });
+        }
+
+        Ok(parent_id)
+    }
+
+    pub fn list_folders(&self) -> Result<Vec<Folder>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!("{} ORDER BY p.path", FOLDER_SELECT))?;
+
+        let folders = stmt.query_map([], Self::row_to_folder)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(folders)
+    }
+
+    pub fn create_folder(&self, name: &str, parent_id: Option<i64>) -> Result<Folder> {
+        let conn = self.conn.lock().unwrap();
+        let name = Self::check_folder_name(name)?;
+        if let Some(parent_id) = parent_id {
+            Self::query_folder(&conn, parent_id)?;
+        }
+        Self::ensure_unique_folder_name(&conn, parent_id, name, None)?;
+
+        conn.execute(
+            "INSERT INTO folders (name, parent_id, created_at) VALUES (?1, ?2, ?3)",
+            params![name, parent_id, chrono::Utc::now().to_rfc3339()],
+        )?;
+
+        Self::query_folder(&conn, conn.last_insert_rowid())
+    }
+
+    pub fn rename_folder(&self, id: i64, name: &str) -> Result<Folder> {
+        let conn = self.conn.lock().unwrap();
+        let name = Self::check_folder_name(name)?;
+        let folder = Self::query_folder(&conn, id)?;
+        Self::ensure_unique_folder_name(&conn, folder.parent_id, name, Some(id))?;
+
+        conn.execute("UPDATE folders SET name = ?1 WHERE id = ?2", params![name, id])?;
+        Self::query_folder(&conn, id)
+    }
+
+    /// 移动文件夹到新的父文件夹下，None 表示移动到顶层
+    pub fn move_folder(&self, id: i64, new_parent_id: Option<i64>) -> Result<Folder> {
+        let conn = self.conn.lock().unwrap();
+        let folder = Self::query_folder(&conn, id)?;
+
+        if let Some(parent_id) = new_parent_id {
+            // 不能移动到自身或其子孙文件夹下
+            let is_descendant: bool = conn.query_row(
+                "WITH RECURSIVE ancestors(id, parent_id) AS (
+                    SELECT id, parent_id FROM folders WHERE id = ?1
+                    UNION ALL
+                    SELECT f.id, f.parent_id FROM folders f JOIN ancestors a ON f.id = a.parent_id
+                 )
+                 SELECT EXISTS(SELECT 1 FROM ancestors WHERE id = ?2)",
+                params![parent_id, id],
+                |row| row.get(0),
+            )?;
+            if is_descendant {
+                bail!("不能将文件夹移动到自身或其子文件夹下");
+            }
+            Self::query_folder(&conn, parent_id)?;
+        }
+        Self::ensure_unique_folder_name(&conn, new_parent_id, &folder.name, Some(id))?;
+
+        conn.execute(
+            "UPDATE folders SET parent_id = ?1 WHERE id = ?2",
+            params![new_parent_id, id],
+        )?;
+        Self::query_folder(&conn, id)
+    }
+
+    /// 删除文件夹。Cascade 同时删除子文件夹和其中的书签，
+    /// Reparent 将子文件夹和书签移动到被删除文件夹的父级
+    pub fn delete_folder(&self, id: i64, mode: DeleteFolderMode) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let folder = Self::query_folder(&tx, id)?;
+
+        match mode {
+            DeleteFolderMode::Cascade => {
+                tx.execute(
+                    "WITH RECURSIVE subtree(id) AS (
+                        SELECT ?1
+                        UNION ALL
+                        SELECT f.id FROM folders f JOIN subtree s ON f.parent_id = s.id
+                     )
+                     DELETE FROM bookmarks WHERE folder_id IN (SELECT id FROM subtree)",
+                    params![id],
+                )?;
+            }
+            DeleteFolderMode::Reparent => {
+                let mut stmt = tx.prepare("SELECT id, name FROM folders WHERE parent_id = ?1")?;
+                let children = stmt
+                    .query_map(params![id], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+                    .collect::<Result<Vec<_>, _>>()?;
+                drop(stmt);
+                for (child_id, child_name) in children {
+                    Self::ensure_unique_folder_name(&tx, folder.parent_id, &child_name, Some(child_id))?;
+                }
+
+                tx.execute(
+                    "UPDATE folders SET parent_id = ?1 WHERE parent_id = ?2",
+                    params![folder.parent_id, id],
+                )?;
+                tx.execute(
+                    "UPDATE bookmarks SET folder_id = ?1 WHERE folder_id = ?2",
+                    params![folder.parent_id, id],
+                )?;
+            }
+        }
+
+        // 子文件夹通过外键级联删除
+        tx.execute("DELETE FROM folders WHERE id = ?1", params![id])?;
+        tx.commit()?;
+
+        drop(conn);
+        if matches!(mode, DeleteFolderMode::Cascade) {
+            // 删除的书签可能带有提醒
+            self.reminder_changes.notify_one();
+        }
+        Ok(())
+    }
+
+    fn row_to_saved_search(row: &rusqlite::Row) -> rusqlite::Result<SavedSearch> {
+        let tags_json: String = row.get(4)?;
+
+        Ok(SavedSearch {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            query: row.get(2)?,
+            folder_id: row.get(3)?,
+            tags: serde_json::from_str(&tags_json).unwrap_or_default(),
+            min_visits: row.get(5)?,
+            created_at: row.get(6)?,
+            updated_at: row.get(7)?,
+        })
+    }
+
+    fn validate_saved_search(input: &SavedSearchInput) -> Result<()> {
+        if input.name.trim().is_empty() {
+            bail!("名称不能为空");
+        }
+        // 保存前检查语法，避免保存无法执行的搜索
+        query::parse(&input.query)?;
+        Ok(())
+    }
+
+    pub fn get_saved_search(&self, id: i64) -> Result<SavedSearch> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, name, query, folder_id, tags, min_visits, created_at, updated_at
+             FROM saved_searches WHERE id = ?1",
+            params![id],
+            Self::row_to_saved_search,
+        )
+        .optional()?
+        .ok_or_else(|| anyhow!("保存的搜索不存在: {}", id))
+    }
+
+    pub fn list_saved_searches(&self) -> Result<Vec<SavedSearch>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, query, folder_id, tags, min_visits, created_at, updated_at
+             FROM saved_searches ORDER BY name"
+        )?;
+
+        let searches = stmt.query_map([], Self::row_to_saved_search)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(searches)
+    }
+
+    pub fn create_saved_search(&self, input: SavedSearchInput) -> Result<SavedSearch> {
+        Self::validate_saved_search(&input)?;
+
+        let conn = self.conn.lock().unwrap();
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO saved_searches (name, query, folder_id, tags, min_visits, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                input.name.trim(),
+                input.query.trim(),
+                input.folder_id,
+                serde_json::to_string(&input.tags)?,
+                input.min_visits,
+                now,
+                now,
+            ],
+        )?;
+
+        let id = conn.last_insert_rowid();
+        drop(conn);
+        self.get_saved_search(id)
+    }
+
+    pub fn update_saved_search(&self, id: i64, input: SavedSearchInput) -> Result<SavedSearch> {
+        Self::validate_saved_search(&input)?;
+
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            "UPDATE saved_searches SET
+                name = ?1, query = ?2, folder_id = ?3, tags = ?4, min_visits = ?5, updated_at = ?6
+             WHERE id = ?7",
+            params![
+                input.name.trim(),
+                input.query.trim(),
+                input.folder_id,
+                serde_json::to_string(&input.tags)?,
+                input.min_visits,
+                chrono::Utc::now().to_rfc3339(),
+                id,
+            ],
+        )?;
+        if updated == 0 {
+            bail!("保存的搜索不存在: {}", id);
+        }
+
+        drop(conn);
+        self.get_saved_search(id)
+    }
+
+    pub fn delete_saved_search(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM saved_searches WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// 执行保存的搜索，返回当前匹配的书签。搜索语句与附加过滤条件之间为“且”
+    pub fn evaluate_saved_search(&self, id: i64) -> Result<Vec<Bookmark>> {
+        let search = self.get_saved_search(id)?;
+
+        let mut filters = Vec::new();
+        for tag in &search.tags {
+            filters.push(Expr::Term(Term::Tag(tag.clone())));
+        }
+        if let Some(folder_id) = search.folder_id {
+            let path = {
+                let conn = self.conn.lock().unwrap();
+                Self::query_folder(&conn, folder_id)?.path
+            };
+            filters.push(Expr::Term(Term::Folder(path)));
+        }
+        if let Some(min_visits) = search.min_visits {
+            filters.push(Expr::Term(Term::Visited(Comparison::Ge, min_visits)));
+        }
+
+        // 没有附加条件时保留全文搜索的相关度排序
+        if filters.is_empty() {
+            if search.query.trim().is_empty() {
+                return self.get_all_bookmarks();
+            }
+            let results = self.search_bookmarks(&search.query)?;
+            return Ok(results.into_iter().map(|r| r.bookmark).collect());
+        }
+
+        if let Some(expr) = query::parse(&search.query)? {
+            filters.insert(0, expr);
+        }
+        self.query_bookmarks(&Expr::And(filters))
+    }
+
+    /// 查找规范化 URL 相同的书签
+    pub fn find_duplicates(&self) -> Result<Vec<DuplicateGroup>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {}, bookmarks.canonical_url FROM bookmarks
+             WHERE bookmarks.canonical_url IN (
+                SELECT canonical_url FROM bookmarks GROUP BY canonical_url HAVING COUNT(*) > 1
+             )
+             ORDER BY bookmarks.canonical_url, bookmarks.created_at, bookmarks.id",
+            BOOKMARK_COLUMNS
+        ))?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(BOOKMARK_COLUMN_COUNT)?, Self::row_to_bookmark(row)?))
+        })?;
+
+        let mut groups: Vec<DuplicateGroup> = Vec::new();
+        for row in rows {
+            let (canonical_url, bookmark) = row?;
+            match groups.last_mut() {
+                Some(group) if group.canonical_url == canonical_url => group.bookmarks.push(bookmark),
+                _ => groups.push(DuplicateGroup {
+                    canonical_url,
+                    bookmarks: vec![bookmark],
+                }),
+            }
+        }
+
+        Ok(groups)
+    }
+
+    /// 将 merge_ids 中的书签合并到 keep_id 并删除它们：
+    /// 标签取并集，备注拼接，访问次数累加，保留最早的创建时间和最近的访问时间，
+    /// 提醒连同历史移到保留的书签，文件夹和图标优先使用保留书签自身的值
+    pub fn merge_bookmarks(&self, keep_id: i64, merge_ids: &[i64]) -> Result<Bookmark> {
+        let mut merged = self.get_bookmark(keep_id)?;
+        let others = merge_ids
+            .iter()
+            .filter(|id| **id != keep_id)
+            .map(|id| self.get_bookmark(*id))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut notes: Vec<String> = merged.notes.iter().cloned().collect();
+        for other in &others {
+            for tag in &other.tags {
+                if !merged.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                    merged.tags.push(tag.clone());
+                }
+            }
+            if let Some(note) = other.notes.as_ref().filter(|n| !n.trim().is_empty()) {
+                if !notes.contains(note) {
+                    notes.push(note.clone());
+                }
+            }
+            merged.visit_count += other.visit_count;
+            if other.last_visited > merged.last_visited {
+                merged.last_visited = other.last_visited.clone();
+            }
+            if other.created_at < merged.created_at {
+                merged.created_at = other.created_at.clone();
+            }
+            merged.folder_id = merged.folder_id.or(other.folder_id);
+            merged.icon_url = merged.icon_url.take().or_else(|| other.icon_url.clone());
+            merged.keyword = merged.keyword.take().or_else(|| other.keyword.clone());
+            // 其他书签的提醒移到保留的书签，与已有提醒时间相同的不重复保留
+            for reminder in &other.reminders {
+                if !merged.reminders.iter().any(|r| schedule::same_schedule(r, reminder)) {
+                    merged.reminders.push(reminder.clone());
+                }
+            }
+        }
+        merged.notes = if notes.is_empty() { None } else { Some(notes.join("\n\n")) };
+
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE bookmarks SET
+                folder_id = ?1, icon_url = ?2, notes = ?3, visit_count = ?4,
+                last_visited = ?5, created_at = ?6, updated_at = ?7, keyword = ?9
+             WHERE id = ?8",
+            params![
+                merged.folder_id,
+                merged.icon_url,
+                merged.notes,
+                merged.visit_count,
+                merged.last_visited,
+                merged.created_at,
+                chrono::Utc::now().to_rfc3339(),
+                keep_id,
+                merged.keyword,
+            ],
+        )?;
+        Self::set_bookmark_tags(&tx, keep_id, &merged.tags)?;
+        for id in merged.reminders.iter().filter_map(|r| r.id) {
+            tx.execute("UPDATE reminders SET bookmark_id = ?1 WHERE id = ?2", params![keep_id, id])?;
+            tx.execute(
+                "UPDATE reminder_events SET bookmark_id = ?1 WHERE reminder_id = ?2",
+                params![keep_id, id],
+            )?;
+        }
+        for other in &others {
+            tx.execute("DELETE FROM bookmarks WHERE id = ?1", params![other.id])?;
+        }
+        tx.commit()?;
+
+        drop(conn);
+        self.reminder_changes.notify_one();
+        self.get_bookmark(keep_id)
+    }
+
+    /// 边解析边导入，每 IMPORT_BATCH_SIZE 条提交一次事务，批次之间释放数据库锁。
+    ///
+    /// 导入不是一个整体事务：每个批次完成后调用 on_progress，返回 false 时停止导入，
+    /// 已提交的批次会保留；已有批次提交后再解析或写入出错时，不返回错误，而是把原因记在
+    /// report.error 中，报告里的计数只包含已提交的批次。
+    /// dry_run 时每个批次执行后回滚，同样在批次之间释放锁，出错时直接返回错误
+    pub fn import_stream<F>(
+        &self,
+        mut items: impl Iterator<Item = Result<ImportedBookmark>>,
+        policy: ImportPolicy,
+        dry_run: bool,
+        mut on_progress: F,
+    ) -> Result<ImportReport>
+    where
+        F: FnMut(&ImportReport) -> bool,
+    {
+        let mut report = ImportReport {
+            dry_run,
+            ..Default::default()
+        };
+        let mut next_batch = || -> Result<Vec<ImportedBookmark>> {
+            items.by_ref().take(IMPORT_BATCH_SIZE).collect()
+        };
+
+        // 试导入时每批执行后回滚，后面的批次看不到前面批次的书签，
+        // 用这里记下的网址判断重复
+        let mut imported = HashSet::new();
+        let mut committed_batches = 0;
+        loop {
+            // 批次失败时把计数恢复到上一个已提交的批次
+            let committed = (report.created, report.updated, report.skipped, report.failed.len());
+            // 先在锁外解析下一批
+            let result = next_batch().and_then(|batch| {
+                if batch.is_empty() {
+                    return Ok(false);
+                }
+                let mut conn = self.conn.lock().unwrap();
+                let mut tx = conn.transaction()?;
+                if dry_run {
+                    let batch = Self::skip_imported(batch, policy, &mut imported, &mut report);
+                    Self::import_batch(&mut tx, batch, policy, &mut report)?;
+                    tx.rollback()?;
+                } else {
+                    Self::import_batch(&mut tx, batch, policy, &mut report)?;
+                    tx.commit()?;
+                }
+                Ok(true)
+            });
+            match result {
+                Ok(true) => committed_batches += 1,
+                Ok(false) => break,
+                Err(e) if !dry_run && committed_batches > 0 => {
+                    (report.created, report.updated, report.skipped) = (committed.0, committed.1, committed.2);
+                    report.failed.truncate(committed.3);
+                    report.error = Some(e.to_string());
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+            if !on_progress(&report) {
+                report.cancelled = true;
+                break;
+            }
+        }
+        if !dry_run {
+            self.reminder_changes.notify_one();
+        }
+
+        Ok(report)
+    }
+
+    // 试导入时处理本次导入中已经出现过的网址：按策略计为跳过或更新，其余的交给 import_batch
+    fn skip_imported(
+        items: Vec<ImportedBookmark>,
+        policy: ImportPolicy,
+        imported: &mut HashSet<String>,
+        report: &mut ImportReport,
+    ) -> Vec<ImportedBookmark> {
+        if matches!(policy, ImportPolicy::AlwaysCreate) {
+            return items;
+        }
+        let mut rest = Vec::new();
+        for item in items {
+            let url = item.url.trim();
+            if url::Url::parse(url).is_err() || imported.insert(canonical_url::canonicalize(url)) {
+                rest.push(item);
+            } else if matches!(policy, ImportPolicy::SkipExisting) {
+                report.skipped += 1;
+            } else {
+                report.updated += 1;
+            }
+        }
+        rest
+    }
+
+    fn import_batch(
+        tx: &mut Transaction,
+        items: Vec<ImportedBookmark>,
+        policy: ImportPolicy,
+        report: &mut ImportReport,
+    ) -> Result<()> {
+        for item in items {
+            // 每条记录使用独立的保存点，单条失败不影响其他记录
+            let sp = tx.savepoint()?;
+            match Self::import_one(&sp, &item, policy) {
+                Ok(outcome) => {
+                    sp.commit()?;
+                    match outcome {
+                        ImportOutcome::Created => report.created += 1,
+                        ImportOutcome::Updated => report.updated += 1,
+                        ImportOutcome::Skipped => report.skipped += 1,
+                    }
+                }
+                Err(e) => {
+                    drop(sp); // 未提交的保存点在 drop 时回滚
+                    report.failed.push(ImportFailure {
+                        title: item.title,
+                        url: item.url,
+                        reason: e.to_string(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn import_one(conn: &Connection, item: &ImportedBookmark, policy: ImportPolicy) -> Result<ImportOutcome> {
+        let url = item.url.trim();
+        if url.is_empty() {
+            bail!("URL 为空");
+        }
+        if url::Url::parse(url).is_err() {
+            bail!("无效的 URL");
+        }
+
+        let existing: Option<i64> = match policy {
+            ImportPolicy::AlwaysCreate => None,
+            _ => conn
+                .query_row(
+                    "SELECT id FROM bookmarks WHERE canonical_url = ?1 ORDER BY created_at LIMIT 1",
+                    params![canonical_url::canonicalize(url)],
+                    |row| row.get(0),
+                )
+                .optional()?,
+        };
+
+        if existing.is_some() && matches!(policy, ImportPolicy::SkipExisting) {
+            return Ok(ImportOutcome::Skipped);
+        }
+
+        let folder_id = Self::ensure_folder_path(conn, &item.folder_path)?;
+        let title = if item.title.trim().is_empty() { url } else { item.title.trim() };
+        // 标签名不能包含逗号，和文件夹名中的 / 一样换成全角字符
+        let tags: Vec<String> = item.tags.iter().map(|t| t.replace(',', "，")).collect();
+
+        match existing {
+            Some(id) => {
+                // 更新已有书签：标题和备注以导入为准，文件夹只在导入数据有时覆盖，标签取并集，
+                // 访问次数和最后访问时间取较大值
+                conn.execute(
+                    "UPDATE bookmarks SET
+                        title = ?1,
+                        folder_id = COALESCE(?2, folder_id),
+                        icon_url = COALESCE(?3, icon_url),
+                        notes = COALESCE(?4, notes),
+                        keyword = COALESCE(?5, keyword),
+                        visit_count = MAX(visit_count, COALESCE(?6, 0)),
+                        last_visited = CASE
+                            WHEN last_visited IS NULL OR ?7 > last_visited THEN COALESCE(?7, last_visited)
+                            ELSE last_visited END,
+                        updated_at = ?8
+                     WHERE id = ?9",
+                    params![
+                        title,
+                        folder_id,
+                        item.icon_url,
+                        item.notes,
+                        item.keyword,
+                        item.visit_count,
+                        item.last_visited,
+                        chrono::Utc::now().to_rfc3339(),
+                        id,
+                    ],
+                )?;
+                for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+                    conn.execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", params![tag])?;
+                    conn.execute(
+                        "INSERT OR IGNORE INTO bookmark_tags (bookmark_id, tag_id)
+                         SELECT ?1, id FROM tags WHERE name = ?2",
+                        params![id, tag],
+                    )?;
+                }
+                // 提醒和标签一样取并集，时间相同的视为同一个
+                let existing = Self::query_reminders(conn, id)?;
+                for reminder in &item.reminders {
+                    if !existing.iter().any(|r| schedule::same_schedule(r, reminder)) {
+                        let mut reminder = Reminder { id: None, ..reminder.clone() };
+                        schedule::reschedule(None, &mut reminder, chrono::Utc::now());
+                        Self::insert_reminder(conn, id, &reminder)?;
+                    }
+                }
+                Ok(ImportOutcome::Updated)
+            }
+            None => {
+                let input = CreateBookmarkInput {
+                    title: title.to_string(),
+                    url: url.to_string(),
+                    folder_id,
+                    tags,
+                    icon_url: item.icon_url.clone(),
+                    notes: item.notes.clone(),
+                    keyword: item.keyword.clone(),
+                    reminders: item.reminders.clone(),
+                    exclude_from_digest: item.exclude_from_digest,
+                };
+                // 保留来源中的创建和修改时间
+                let now = chrono::Utc::now().to_rfc3339();
+                let created_at = item.created_at.as_deref().unwrap_or(&now);
+                let updated_at = item.updated_at.as_deref().unwrap_or(created_at);
+                let id = Self::insert_bookmark(conn, &input, created_at, updated_at)?;
+                if item.visit_count.is_some() || item.last_visited.is_some() {
+                    conn.execute(
+                        "UPDATE bookmarks SET visit_count = ?1, last_visited = ?2 WHERE id = ?3",
+                        params![item.visit_count.unwrap_or(0), item.last_visited, id],
+                    )?;
+                }
+                Ok(ImportOutcome::Created)
+            }
+        }
+    }
+}
+
+enum ImportOutcome {
+    Created,
+    Updated,
+    Skipped,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ReminderFrequency;
+
+    #[test]
+    fn unreadable_reminder_is_an_error() {
+        let db = Database::new(":memory:").unwrap();
+        let reminder = Reminder {
+            id: None,
+            enabled: true,
+            frequency: ReminderFrequency::Daily,
+            time: "09:00".into(),
+            days: vec![],
+            last_reminded: None,
+            next_reminder: None,
+            catch_up: CatchUpPolicy::default(),
+            timezone: None,
+            title_template: None,
+            message_template: None,
+        };
+        let bookmark = db
+            .create_bookmark(CreateBookmarkInput {
+                title: "Rust".into(),
+                url: "https://www.rust-lang.org/".into(),
+                folder_id: None,
+                tags: vec![],
+                icon_url: None,
+                notes: None,
+                keyword: None,
+                reminders: vec![reminder],
+                exclude_from_digest: false,
+            })
+            .unwrap();
+        let id = bookmark.id.unwrap();
+
+        // 例如新版本写入的频率
+        db.conn
+            .lock()
+            .unwrap()
+            .execute("UPDATE reminders SET frequency = '{\"type\":\"lunar\"}'", [])
+            .unwrap();
+        assert!(db.get_bookmark(id).is_err());
+        assert!(db.get_all_bookmarks().is_err());
+    }
+
+    #[test]
+    fn import_error_keeps_committed_batches() {
+        let item = |i: usize| {
+            Ok(ImportedBookmark {
+                title: format!("第 {} 个", i),
+                url: format!("https://example.com/{}", i),
+                ..Default::default()
+            })
+        };
+        let truncated = || Err(anyhow!("文件不完整"));
+
+        let db = Database::new(":memory:").unwrap();
+        let items = (0..IMPORT_BATCH_SIZE + 10).map(item).chain([truncated()]);
+        let report = db.import_stream(items, ImportPolicy::SkipExisting, false, |_| true).unwrap();
+        assert_eq!(report.created, IMPORT_BATCH_SIZE);
+        assert_eq!(report.error.as_deref(), Some("文件不完整"));
+        assert_eq!(db.get_all_bookmarks().unwrap().len(), IMPORT_BATCH_SIZE);
+
+        // 还没有提交任何批次时直接返回错误
+        let db = Database::new(":memory:").unwrap();
+        let items = (0..10).map(item).chain([truncated()]);
+        assert!(db.import_stream(items, ImportPolicy::SkipExisting, false, |_| true).is_err());
+        assert!(db.get_all_bookmarks().unwrap().is_empty());
+    }
 }
@@ -0,0 +1,67 @@
+use crate::models::{Bookmark, CsvColumn};
+use anyhow::Result;
+
+/// 按指定的列生成 CSV，第一行是列名。
+///
+/// 文件夹写成以 / 分隔的路径，标签以逗号分隔，提醒写成 JSON 数组
+pub fn write_csv(bookmarks: &[Bookmark], columns: &[CsvColumn]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(columns.iter().map(|c| c.name()))?;
+
+    for bookmark in bookmarks {
+        let record = columns.iter().map(|column| cell(bookmark, *column)).collect::<Result<Vec<_>>>()?;
+        writer.write_record(&record)?;
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+fn cell(bookmark: &Bookmark, column: CsvColumn) -> Result<String> {
+    let value = match column {
+        CsvColumn::Title => bookmark.title.clone(),
+        CsvColumn::Url => bookmark.url.clone(),
+        CsvColumn::Folder => bookmark.folder_path.clone().unwrap_or_default(),
+        CsvColumn::Tags => bookmark.tags.join(","),
+        CsvColumn::Notes => bookmark.notes.clone().unwrap_or_default(),
+        CsvColumn::Keyword => bookmark.keyword.clone().unwrap_or_default(),
+        CsvColumn::IconUrl => bookmark.icon_url.clone().unwrap_or_default(),
+        CsvColumn::VisitCount => bookmark.visit_count.to_string(),
+        CsvColumn::LastVisited => bookmark.last_visited.clone().unwrap_or_default(),
+        CsvColumn::CreatedAt => bookmark.created_at.clone(),
+        CsvColumn::UpdatedAt => bookmark.updated_at.clone(),
+        CsvColumn::Reminder if bookmark.reminders.is_empty() => String::new(),
+        CsvColumn::Reminder => serde_json::to_string(&bookmark.reminders)?,
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_selected_columns_in_order() {
+        let bookmark = Bookmark {
+            id: Some(1),
+            title: "Hello, world".into(),
+            url: "https://example.com/".into(),
+            folder_id: None,
+            folder_path: None,
+            tags: vec!["a".into(), "b".into()],
+            icon_url: None,
+            notes: None,
+            keyword: None,
+            reminders: vec![],
+            exclude_from_digest: false,
+            visit_count: 2,
+            last_visited: None,
+            created_at: "2024-03-01T08:30:00+00:00".into(),
+            updated_at: "2024-03-01T08:30:00+00:00".into(),
+        };
+        let columns = [CsvColumn::Url, CsvColumn::Title, CsvColumn::Tags, CsvColumn::Folder, CsvColumn::Reminder];
+        assert_eq!(
+            write_csv(&[bookmark], &columns).unwrap(),
+            "url,title,tags,folder,reminder\nhttps://example.com/,\"Hello, world\",\"a,b\",,\n"
+        );
+    }
+}
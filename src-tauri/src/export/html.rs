@@ -0,0 +1,218 @@
+use super::SnapshotFolder;
+use crate::models::{Bookmark, Folder};
+use chrono::DateTime;
+use std::collections::HashMap;
+
+const HEADER: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<!-- This is an automatically generated file.
+     It will be read and overwritten.
+     DO NOT EDIT! -->
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+"#;
+
+/// 生成 Netscape 书签 HTML，可以被 import::parse_html_bookmarks 无损读回。
+///
+/// 文件夹按名称排序，书签按创建时间排序，相同数据总是生成相同的文件。
+/// 不属于任何文件夹的书签放在顶层
+pub fn write_netscape_html(folders: &[Folder], bookmarks: &[Bookmark], snapshots: &[SnapshotFolder]) -> String {
+    let mut children: HashMap<Option<i64>, Vec<&Folder>> = HashMap::new();
+    for folder in folders {
+        children.entry(folder.parent_id).or_default().push(folder);
+    }
+    for list in children.values_mut() {
+        list.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+    }
+
+    let mut contents: HashMap<Option<i64>, Vec<&Bookmark>> = HashMap::new();
+    for bookmark in bookmarks {
+        contents.entry(bookmark.folder_id).or_default().push(bookmark);
+    }
+    for list in contents.values_mut() {
+        list.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    }
+
+    let writer = Writer { children, contents };
+    let mut html = String::from(HEADER);
+    html.push_str("<DL><p>\n");
+    writer.write_level(&mut html, None, 1);
+
+    for snapshot in snapshots {
+        let indent = "    ";
+        html.push_str(&format!("{}<DT><H3>{}</H3>\n", indent, escape(&snapshot.name)));
+        html.push_str(&format!("{}<DL><p>\n", indent));
+        for bookmark in &snapshot.bookmarks {
+            write_bookmark(&mut html, bookmark, 2);
+        }
+        html.push_str(&format!("{}</DL><p>\n", indent));
+    }
+
+    html.push_str("</DL><p>\n");
+    html
+}
+
+struct Writer<'a> {
+    children: HashMap<Option<i64>, Vec<&'a Folder>>,
+    contents: HashMap<Option<i64>, Vec<&'a Bookmark>>,
+}
+
+impl Writer<'_> {
+    // 先写子文件夹再写书签，与浏览器导出的顺序一致
+    fn write_level(&self, html: &mut String, parent: Option<i64>, depth: usize) {
+        let indent = "    ".repeat(depth);
+
+        for folder in self.children.get(&parent).into_iter().flatten() {
+            html.push_str(&format!(
+                "{}<DT><H3{}>{}</H3>\n",
+                indent,
+                timestamp_attr("ADD_DATE", &folder.created_at),
+                escape(&folder.name)
+            ));
+            html.push_str(&format!("{}<DL><p>\n", indent));
+            self.write_level(html, Some(folder.id), depth + 1);
+            html.push_str(&format!("{}</DL><p>\n", indent));
+        }
+
+        for bookmark in self.contents.get(&parent).into_iter().flatten() {
+            write_bookmark(html, bookmark, depth);
+        }
+    }
+}
+
+fn write_bookmark(html: &mut String, bookmark: &Bookmark, depth: usize) {
+    let indent = "    ".repeat(depth);
+
+    let mut attrs = format!(" HREF=\"{}\"", escape(&bookmark.url));
+    attrs.push_str(&timestamp_attr("ADD_DATE", &bookmark.created_at));
+    attrs.push_str(&timestamp_attr("LAST_MODIFIED", &bookmark.updated_at));
+    if let Some(icon) = bookmark.icon_url.as_deref().filter(|i| !i.is_empty()) {
+        // ICON 只能是 data URI，普通地址使用 Firefox 的 ICON_URI
+        let name = if icon.starts_with("data:") { "ICON" } else { "ICON_URI" };
+        attrs.push_str(&format!(" {}=\"{}\"", name, escape(icon)));
+    }
+    if !bookmark.tags.is_empty() {
+        attrs.push_str(&format!(" TAGS=\"{}\"", escape(&bookmark.tags.join(","))));
+    }
+    if let Some(keyword) = bookmark.keyword.as_deref().filter(|k| !k.is_empty()) {
+        attrs.push_str(&format!(" SHORTCUTURL=\"{}\"", escape(keyword)));
+    }
+
+    html.push_str(&format!("{}<DT><A{}>{}</A>\n", indent, attrs, escape(&bookmark.title)));
+    if let Some(notes) = bookmark.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        html.push_str(&format!("{}<DD>{}\n", indent, escape(notes)));
+    }
+}
+
+fn timestamp_attr(name: &str, rfc3339: &str) -> String {
+    match DateTime::parse_from_rfc3339(rfc3339) {
+        Ok(time) => format!(" {}=\"{}\"", name, time.timestamp()),
+        Err(_) => String::new(),
+    }
+}
+
+/// 转义文本和属性值中的 HTML 特殊字符
+pub fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{HtmlBookmarkStream, ImportedBookmark};
+
+    fn folder(id: i64, name: &str, parent_id: Option<i64>, path: &str) -> Folder {
+        Folder {
+            id,
+            name: name.into(),
+            parent_id,
+            path: path.into(),
+            bookmark_count: 0,
+            created_at: "2024-01-01T00:00:00+00:00".into(),
+        }
+    }
+
+    fn bookmark(id: i64, title: &str, url: &str, folder_id: Option<i64>) -> Bookmark {
+        Bookmark {
+            id: Some(id),
+            title: title.into(),
+            url: url.into(),
+            folder_id,
+            folder_path: None,
+            tags: Vec::new(),
+            icon_url: None,
+            notes: None,
+            keyword: None,
+            reminders: Vec::new(),
+            exclude_from_digest: false,
+            visit_count: 0,
+            last_visited: None,
+            created_at: format!("2024-03-0{}T08:30:00+00:00", id),
+            updated_at: format!("2024-03-0{}T09:45:00+00:00", id),
+        }
+    }
+
+    fn read_back(html: &str) -> Vec<ImportedBookmark> {
+        HtmlBookmarkStream::new(html.as_bytes()).collect::<anyhow::Result<_>>().unwrap()
+    }
+
+    #[test]
+    fn round_trips_through_html_import() {
+        let folders = [
+            folder(1, "开发", None, "开发"),
+            folder(2, "Rust & <Go>", Some(1), "开发/Rust & <Go>"),
+        ];
+        let mut rust = bookmark(1, "Rust \"官方\" <站点>", "https://www.rust-lang.org/?a=1&b=2", Some(2));
+        rust.tags = vec!["rust".into(), "编程".into(), "a&b".into()];
+        rust.icon_url = Some("data:image/png;base64,iVBORw0KGgo=".into());
+        rust.notes = Some("先读 <The Book> & 'Rustlings'".into());
+        rust.keyword = Some("rs".into());
+        let mut top = bookmark(2, "顶层", "https://example.com/", None);
+        top.icon_url = Some("https://example.com/favicon.ico".into());
+
+        let html = write_netscape_html(&folders, &[rust.clone(), top.clone()], &[]);
+        let imported = read_back(&html);
+        assert_eq!(imported.len(), 2);
+
+        let back = &imported[0];
+        assert_eq!(back.title, rust.title);
+        assert_eq!(back.url, rust.url);
+        assert_eq!(back.folder_path, ["开发", "Rust & <Go>"]);
+        assert_eq!(back.tags, rust.tags);
+        assert_eq!(back.icon_url, rust.icon_url);
+        assert_eq!(back.notes, rust.notes);
+        assert_eq!(back.keyword, rust.keyword);
+        assert_eq!(back.created_at.as_deref(), Some(rust.created_at.as_str()));
+        assert_eq!(back.updated_at.as_deref(), Some(rust.updated_at.as_str()));
+
+        let back = &imported[1];
+        assert_eq!(back.title, top.title);
+        assert!(back.folder_path.is_empty());
+        assert!(back.tags.is_empty());
+        assert_eq!(back.icon_url, top.icon_url);
+        assert_eq!(back.notes, None);
+    }
+
+    #[test]
+    fn snapshots_become_top_level_folders() {
+        let snapshot = SnapshotFolder {
+            name: "未读".into(),
+            bookmarks: vec![bookmark(3, "文章", "https://example.com/post", Some(1))],
+        };
+        let html = write_netscape_html(&[], &[], &[snapshot]);
+        let imported = read_back(&html);
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].folder_path, ["未读"]);
+    }
+}
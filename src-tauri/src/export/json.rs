@@ -0,0 +1,28 @@
+use crate::models::{Bookmark, BookmarkBackup};
+use anyhow::Result;
+
+/// 生成 JSON 备份，包含书签的全部字段（提醒、访问记录、时间戳），
+/// 可以被 import::parse_json_backup 完整恢复
+pub fn write_json(bookmarks: &[Bookmark]) -> Result<String> {
+    let backup = BookmarkBackup {
+        format: BookmarkBackup::FORMAT.to_string(),
+        version: BookmarkBackup::VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        bookmarks: bookmarks.to_vec(),
+    };
+    Ok(serde_json::to_string_pretty(&backup)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_format_and_version() {
+        let value: serde_json::Value = serde_json::from_str(&write_json(&[]).unwrap()).unwrap();
+        assert_eq!(value["format"], BookmarkBackup::FORMAT);
+        assert_eq!(value["version"], BookmarkBackup::VERSION);
+        assert!(value["exported_at"].is_string());
+        assert_eq!(value["bookmarks"], serde_json::json!([]));
+    }
+}
@@ -0,0 +1,15 @@
+mod csv;
+mod html;
+mod json;
+
+pub use self::csv::write_csv;
+pub use html::write_netscape_html;
+pub use json::write_json;
+
+use crate::models::Bookmark;
+
+/// 导出时附加的只读快照文件夹（例如智能文件夹的当前结果）
+pub struct SnapshotFolder {
+    pub name: String,
+    pub bookmarks: Vec<Bookmark>,
+}
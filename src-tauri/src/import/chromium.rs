@@ -0,0 +1,191 @@
+use super::ImportedBookmark;
+use anyhow::{bail, Context, Result};
+use chrono::DateTime;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// WebKit 时间戳从 1601-01-01 起算，与 Unix 纪元相差的秒数
+const WEBKIT_EPOCH_OFFSET: i64 = 11_644_473_600;
+
+#[derive(Deserialize)]
+struct BookmarksFile {
+    roots: Roots,
+}
+
+#[derive(Deserialize)]
+struct Roots {
+    bookmark_bar: Option<Node>,
+    other: Option<Node>,
+    synced: Option<Node>,
+}
+
+#[derive(Deserialize)]
+struct Node {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    name: String,
+    url: Option<String>,
+    date_added: Option<String>,
+    date_modified: Option<String>,
+    date_last_used: Option<String>,
+    #[serde(default)]
+    children: Vec<Node>,
+}
+
+/// 解析 Chromium 系浏览器（Chrome、Edge、Brave、Vivaldi）的 `Bookmarks` JSON 文件。
+///
+/// 可以传入文件本身、配置目录（如 `Default`）或用户数据目录，
+/// 后两种情况会在目录中查找 `Bookmarks` 文件
+pub fn parse_chromium_bookmarks(path: &str) -> Result<Vec<ImportedBookmark>> {
+    let file_path = locate_bookmarks_file(Path::new(path))?;
+    let json = fs::read_to_string(&file_path)
+        .with_context(|| format!("无法读取 {}", file_path.display()))?;
+    parse_chromium_str(&json)
+}
+
+pub fn parse_chromium_str(json: &str) -> Result<Vec<ImportedBookmark>> {
+    let file: BookmarksFile = serde_json::from_str(json).context("不是有效的 Chromium 书签文件")?;
+
+    let mut bookmarks = Vec::new();
+    let roots = [
+        (file.roots.bookmark_bar, "Bookmarks Bar"),
+        (file.roots.other, "Other Bookmarks"),
+        (file.roots.synced, "Mobile Bookmarks"),
+    ];
+    for (root, default_name) in roots {
+        if let Some(root) = root {
+            let name = if root.name.trim().is_empty() { default_name } else { root.name.trim() };
+            walk(&root.children, &[name.to_string()], &mut bookmarks);
+        }
+    }
+
+    Ok(bookmarks)
+}
+
+fn walk(nodes: &[Node], path: &[String], bookmarks: &mut Vec<ImportedBookmark>) {
+    for node in nodes {
+        match node.kind.as_str() {
+            "folder" => {
+                let mut child_path = path.to_vec();
+                child_path.push(node.name.trim().to_string());
+                walk(&node.children, &child_path, bookmarks);
+            }
+            "url" => {
+                let url = match node.url.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+                    Some(url) => url.to_string(),
+                    None => continue,
+                };
+                let title = node.name.trim();
+
+                bookmarks.push(ImportedBookmark {
+                    title: if title.is_empty() { url.clone() } else { title.to_string() },
+                    url,
+                    folder_path: path.to_vec(),
+                    last_visited: node.date_last_used.as_deref().and_then(from_webkit_time),
+                    created_at: node.date_added.as_deref().and_then(from_webkit_time),
+                    updated_at: node.date_modified.as_deref().and_then(from_webkit_time),
+                    ..Default::default()
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 依次尝试：文件本身、目录下的 Bookmarks、目录下 Default 配置中的 Bookmarks
+fn locate_bookmarks_file(path: &Path) -> Result<PathBuf> {
+    if path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+    if path.is_dir() {
+        for candidate in [path.join("Bookmarks"), path.join("Default").join("Bookmarks")] {
+            if candidate.is_file() {
+                return Ok(candidate);
+            }
+        }
+        bail!("在 {} 中没有找到 Bookmarks 文件", path.display());
+    }
+    bail!("{} 不存在", path.display())
+}
+
+/// WebKit 时间戳是从 1601-01-01 起的微秒数，以字符串保存，0 表示未设置
+fn from_webkit_time(value: &str) -> Option<String> {
+    let micros: i64 = value.trim().parse().ok()?;
+    if micros <= 0 {
+        return None;
+    }
+    let seconds = micros / 1_000_000 - WEBKIT_EPOCH_OFFSET;
+    let nanos = (micros % 1_000_000 * 1_000) as u32;
+    if seconds <= 0 {
+        return None;
+    }
+    DateTime::from_timestamp(seconds, nanos).map(|dt| dt.to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOKMARKS: &str = include_str!("../../tests/fixtures/chromium_Bookmarks.json");
+
+    #[test]
+    fn folder_paths_under_roots() {
+        let bookmarks = parse_chromium_str(BOOKMARKS).unwrap();
+        let links: Vec<(&str, &str, String)> = bookmarks
+            .iter()
+            .map(|b| (b.title.as_str(), b.url.as_str(), b.folder_path.join("/")))
+            .collect();
+        assert_eq!(
+            links,
+            [
+                ("The Rust Book", "https://doc.rust-lang.org/book/", "书签栏/开发".to_string()),
+                // 没有标题时用网址代替
+                ("https://tokio.rs/", "https://tokio.rs/", "书签栏/开发/异步".to_string()),
+                // 根节点没有名称时使用默认名称
+                ("Hacker News", "https://news.ycombinator.com/", "Other Bookmarks".to_string()),
+                ("MDN", "https://developer.mozilla.org/", "移动设备书签".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_webkit_time() {
+        let bookmarks = parse_chromium_str(BOOKMARKS).unwrap();
+        let book = &bookmarks[0];
+        assert_eq!(book.created_at.as_deref(), Some("2024-01-17T21:20:00.123456+00:00"));
+        assert_eq!(book.last_visited.as_deref(), Some("2024-01-29T11:06:40+00:00"));
+        assert_eq!(book.updated_at, None);
+
+        // 0 表示未设置
+        assert_eq!(bookmarks[1].created_at.as_deref(), Some("2024-01-17T21:20:00+00:00"));
+        assert_eq!(bookmarks[1].last_visited, None);
+        assert_eq!(bookmarks[3].created_at, None);
+
+        assert_eq!(from_webkit_time("not a number"), None);
+        assert_eq!(from_webkit_time("-1"), None);
+    }
+
+    #[test]
+    fn finds_file_in_profile_directory() {
+        let dir = std::env::temp_dir().join(format!("chromium-import-{}", std::process::id()));
+        let profile = dir.join("Default");
+        fs::create_dir_all(&profile).unwrap();
+        fs::write(profile.join("Bookmarks"), BOOKMARKS).unwrap();
+
+        let from_dir = parse_chromium_bookmarks(dir.to_str().unwrap()).unwrap();
+        let from_profile = parse_chromium_bookmarks(profile.to_str().unwrap()).unwrap();
+        assert_eq!(from_dir.len(), 4);
+        assert_eq!(from_profile.len(), 4);
+        assert!(parse_chromium_bookmarks(dir.join("missing").to_str().unwrap()).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(parse_chromium_bookmarks(dir.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn rejects_other_json() {
+        assert!(parse_chromium_str(r#"{"posts": []}"#).is_err());
+    }
+}
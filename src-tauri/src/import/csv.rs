@@ -0,0 +1,149 @@
+use super::ImportedBookmark;
+use crate::models::CsvColumn;
+use anyhow::{anyhow, bail, Result};
+
+/// 解析带表头的 CSV，列名见 CsvColumn（不区分大小写），不认识的列会被忽略，url 列必须存在。
+///
+/// 空单元格表示没有该字段，文件夹以 / 分隔，标签以逗号分隔，提醒是 JSON 数组
+pub fn parse_csv_bookmarks(file_path: &str) -> Result<Vec<ImportedBookmark>> {
+    let reader = csv::ReaderBuilder::new().flexible(true).from_path(file_path)?;
+    parse_csv_reader(reader)
+}
+
+#[cfg(test)]
+pub fn parse_csv_str(text: &str) -> Result<Vec<ImportedBookmark>> {
+    let reader = csv::ReaderBuilder::new().flexible(true).from_reader(text.as_bytes());
+    parse_csv_reader(reader)
+}
+
+fn parse_csv_reader<R: std::io::Read>(mut reader: csv::Reader<R>) -> Result<Vec<ImportedBookmark>> {
+    // 去掉 Excel 保存时加上的 BOM
+    let columns: Vec<Option<CsvColumn>> = reader
+        .headers()?
+        .iter()
+        .map(|name| CsvColumn::from_name(name.trim_start_matches('\u{feff}')))
+        .collect();
+    if !columns.contains(&Some(CsvColumn::Url)) {
+        bail!("CSV 缺少 url 列");
+    }
+
+    let mut bookmarks = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record?;
+        // 表头是第 1 行
+        let line = index + 2;
+
+        let mut bookmark = ImportedBookmark::default();
+        for (column, value) in columns.iter().zip(record.iter()) {
+            let value = value.trim();
+            let column = match column {
+                Some(column) if !value.is_empty() => *column,
+                _ => continue,
+            };
+            match column {
+                CsvColumn::Title => bookmark.title = value.to_string(),
+                CsvColumn::Url => bookmark.url = value.to_string(),
+                CsvColumn::Folder => bookmark.folder_path = super::split_folder_path(value),
+                CsvColumn::Tags => {
+                    bookmark.tags = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|t| !t.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                }
+                CsvColumn::Notes => bookmark.notes = Some(value.to_string()),
+                CsvColumn::Keyword => bookmark.keyword = Some(value.to_string()),
+                CsvColumn::IconUrl => bookmark.icon_url = Some(value.to_string()),
+                CsvColumn::VisitCount => {
+                    let count = value
+                        .parse()
+                        .map_err(|_| anyhow!("第 {} 行：访问次数不是整数", line))?;
+                    bookmark.visit_count = Some(count);
+                }
+                CsvColumn::LastVisited => bookmark.last_visited = Some(parse_time(value, line)?),
+                CsvColumn::CreatedAt => bookmark.created_at = Some(parse_time(value, line)?),
+                CsvColumn::UpdatedAt => bookmark.updated_at = Some(parse_time(value, line)?),
+                CsvColumn::Reminder => {
+                    // 提醒是 JSON 数组，旧版本导出的是单个提醒对象
+                    let json = if value.starts_with('[') { value.to_string() } else { format!("[{}]", value) };
+                    bookmark.reminders = serde_json::from_str(&json)
+                        .map_err(|e| anyhow!("第 {} 行：提醒格式无效（{}）", line, e))?;
+                }
+            }
+        }
+        bookmarks.push(bookmark);
+    }
+
+    Ok(bookmarks)
+}
+
+/// 时间使用 RFC 3339 格式
+fn parse_time(value: &str, line: usize) -> Result<String> {
+    super::normalize_time(value).ok_or_else(|| anyhow!("第 {} 行：时间 {} 不是 RFC 3339 格式", line, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_bom_and_reads_quoted_fields() {
+        // Excel 保存的文件带 BOM，字段中的逗号、换行和引号需要加引号转义
+        let text = concat!(
+            "\u{feff}",
+            r#"Title,URL,Folder,Tags,Notes
+"Hello, world",https://example.com/,工作/项目,"a, b","第一行
+""第二行"""
+"#
+        );
+        let bookmarks = parse_csv_str(text).unwrap();
+        assert_eq!(bookmarks.len(), 1);
+
+        let bookmark = &bookmarks[0];
+        assert_eq!(bookmark.title, "Hello, world");
+        assert_eq!(bookmark.url, "https://example.com/");
+        assert_eq!(bookmark.folder_path, ["工作", "项目"]);
+        assert_eq!(bookmark.tags, ["a", "b"]);
+        assert_eq!(bookmark.notes.as_deref(), Some("第一行\n\"第二行\""));
+    }
+
+    #[test]
+    fn empty_cells_and_unknown_columns_are_ignored() {
+        let text = "url,title,rating,notes,visit_count\nhttps://example.com/,,5,,\n";
+        let bookmark = &parse_csv_str(text).unwrap()[0];
+        assert_eq!(bookmark.title, "");
+        assert_eq!(bookmark.notes, None);
+        assert_eq!(bookmark.visit_count, None);
+    }
+
+    #[test]
+    fn requires_url_column() {
+        let error = parse_csv_str("title,notes\nA,B\n").unwrap_err().to_string();
+        assert_eq!(error, "CSV 缺少 url 列");
+    }
+
+    #[test]
+    fn errors_report_line_numbers() {
+        let error = |text: &str| parse_csv_str(text).unwrap_err().to_string();
+        assert_eq!(
+            error("url,visit_count\nhttps://a.example.com/,1\nhttps://b.example.com/,many\n"),
+            "第 3 行：访问次数不是整数"
+        );
+        assert_eq!(
+            error("url,created_at\nhttps://a.example.com/,2024-03-01\n"),
+            "第 2 行：时间 2024-03-01 不是 RFC 3339 格式"
+        );
+        assert!(error("url,reminder\nhttps://a.example.com/,{bad\n").starts_with("第 2 行：提醒格式无效"));
+    }
+
+    #[test]
+    fn single_reminder_object_from_older_exports() {
+        let text = r#"url,reminder
+https://example.com/,"{""enabled"":true,""frequency"":{""type"":""daily""},""time"":""09:00"",""days"":[],""last_reminded"":null,""next_reminder"":null}"
+"#;
+        let bookmark = &parse_csv_str(text).unwrap()[0];
+        assert_eq!(bookmark.reminders.len(), 1);
+        assert_eq!(bookmark.reminders[0].time, "09:00");
+    }
+}
@@ -0,0 +1,318 @@
+use super::ImportedBookmark;
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use rusqlite::{Connection, OpenFlags};
+use std::collections::HashMap;
+
+// moz_bookmarks.type
+const TYPE_BOOKMARK: i64 = 1;
+const TYPE_FOLDER: i64 = 2;
+
+const ROOT_GUID: &str = "root________";
+const TAGS_GUID: &str = "tags________";
+
+// 内置根文件夹在新版 Firefox 中标题为空或内部名称，使用界面上的名称
+fn root_folder_name(guid: &str) -> Option<&'static str> {
+    match guid {
+        "menu________" => Some("Bookmarks Menu"),
+        "toolbar_____" => Some("Bookmarks Toolbar"),
+        "unfiled_____" => Some("Other Bookmarks"),
+        "mobile______" => Some("Mobile Bookmarks"),
+        _ => None,
+    }
+}
+
+struct FolderNode {
+    parent: i64,
+    title: String,
+    guid: String,
+}
+
+/// 读取 Firefox 的 places.sqlite（建议使用副本，运行中的 Firefox 会锁定原文件）。
+///
+/// 文件夹结构来自 moz_bookmarks，访问次数来自 moz_places，关键字来自 moz_keywords。
+/// 标签在 Firefox 中是 tags 根目录下的文件夹，其中的条目指向被打标签的网址
+pub fn parse_firefox_places(file_path: &str) -> Result<Vec<ImportedBookmark>> {
+    let conn = Connection::open_with_flags(
+        file_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .with_context(|| format!("无法打开 {}", file_path))?;
+
+    let folders = load_folders(&conn).context("不是有效的 Firefox places.sqlite")?;
+    let tags_root = folders.iter().find(|(_, f)| f.guid == TAGS_GUID).map(|(id, _)| *id);
+    let tags = load_tags(&conn, tags_root)?;
+    let keywords = load_keywords(&conn);
+
+    let mut stmt = conn.prepare(
+        "SELECT b.parent, b.title, b.dateAdded, b.lastModified, b.fk,
+                p.url, p.title, p.visit_count, p.last_visit_date
+         FROM moz_bookmarks b JOIN moz_places p ON p.id = b.fk
+         WHERE b.type = ?1
+         ORDER BY b.parent, b.position",
+    )?;
+
+    let rows = stmt.query_map([TYPE_BOOKMARK], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<i64>>(2)?,
+            row.get::<_, Option<i64>>(3)?,
+            row.get::<_, i64>(4)?,
+            row.get::<_, String>(5)?,
+            row.get::<_, Option<String>>(6)?,
+            row.get::<_, Option<i64>>(7)?,
+            row.get::<_, Option<i64>>(8)?,
+        ))
+    })?;
+
+    let mut bookmarks = Vec::new();
+    for row in rows {
+        let (parent, title, added, modified, place_id, url, place_title, visits, last_visit) = row?;
+
+        // 标签文件夹里的条目只表示标签，不是独立的书签
+        let path = match folder_path(&folders, parent, tags_root) {
+            Some(path) => path,
+            None => continue,
+        };
+        // place: 是智能书签查询，不是网页
+        if url.starts_with("place:") {
+            continue;
+        }
+
+        // 书签没有标题时依次使用网页标题和网址
+        let title = [title, place_title]
+            .into_iter()
+            .flatten()
+            .map(|t| t.trim().to_string())
+            .find(|t| !t.is_empty())
+            .unwrap_or_else(|| url.clone());
+
+        bookmarks.push(ImportedBookmark {
+            title,
+            url,
+            folder_path: path,
+            tags: tags.get(&place_id).cloned().unwrap_or_default(),
+            keyword: keywords.get(&place_id).cloned(),
+            visit_count: visits,
+            last_visited: last_visit.and_then(from_prtime),
+            created_at: added.and_then(from_prtime),
+            updated_at: modified.and_then(from_prtime),
+            ..Default::default()
+        });
+    }
+
+    Ok(bookmarks)
+}
+
+fn load_folders(conn: &Connection) -> Result<HashMap<i64, FolderNode>> {
+    let mut stmt = conn.prepare("SELECT id, parent, title, guid FROM moz_bookmarks WHERE type = ?1")?;
+    let folders = stmt
+        .query_map([TYPE_FOLDER], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                FolderNode {
+                    parent: row.get(1)?,
+                    title: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                    guid: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                },
+            ))
+        })?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(folders)
+}
+
+// place id -> 标签名
+fn load_tags(conn: &Connection, tags_root: Option<i64>) -> Result<HashMap<i64, Vec<String>>> {
+    let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+    let tags_root = match tags_root {
+        Some(id) => id,
+        None => return Ok(tags),
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT b.fk, t.title FROM moz_bookmarks b
+         JOIN moz_bookmarks t ON t.id = b.parent
+         WHERE t.parent = ?1 AND b.type = ?2 AND b.fk IS NOT NULL
+         ORDER BY t.title",
+    )?;
+    let rows = stmt.query_map([tags_root, TYPE_BOOKMARK], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?))
+    })?;
+
+    for row in rows {
+        let (place_id, tag) = row?;
+        if let Some(tag) = tag.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) {
+            tags.entry(place_id).or_default().push(tag);
+        }
+    }
+    Ok(tags)
+}
+
+// place id -> 关键字。旧版本没有 moz_keywords.place_id，此时忽略关键字
+fn load_keywords(conn: &Connection) -> HashMap<i64, String> {
+    let query = || -> rusqlite::Result<HashMap<i64, String>> {
+        let mut stmt = conn.prepare("SELECT place_id, keyword FROM moz_keywords")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    };
+    query().unwrap_or_default()
+}
+
+/// 从书签所在文件夹向上拼出路径，位于标签目录下时返回 None
+fn folder_path(folders: &HashMap<i64, FolderNode>, mut id: i64, tags_root: Option<i64>) -> Option<Vec<String>> {
+    let mut path = Vec::new();
+
+    while let Some(folder) = folders.get(&id) {
+        if Some(id) == tags_root {
+            return None;
+        }
+        if folder.guid == ROOT_GUID {
+            break;
+        }
+        let name = root_folder_name(&folder.guid).unwrap_or(folder.title.as_str());
+        path.push(name.to_string());
+        id = folder.parent;
+    }
+
+    path.reverse();
+    Some(path)
+}
+
+/// Firefox 的 PRTime 是 Unix 微秒
+fn from_prtime(value: i64) -> Option<String> {
+    if value <= 0 {
+        return None;
+    }
+    DateTime::from_timestamp(value / 1_000_000, ((value % 1_000_000) * 1_000) as u32)
+        .map(|dt| dt.to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // places.sqlite 中导入会读取的表和列
+    const SCHEMA: &str = "
+        CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT, title TEXT, visit_count INTEGER, last_visit_date INTEGER);
+        CREATE TABLE moz_bookmarks (
+            id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER, parent INTEGER, position INTEGER,
+            title TEXT, dateAdded INTEGER, lastModified INTEGER, guid TEXT
+        );
+        CREATE TABLE moz_keywords (id INTEGER PRIMARY KEY, keyword TEXT, place_id INTEGER);
+
+        INSERT INTO moz_places VALUES
+            (1, 'https://doc.rust-lang.org/book/', 'The Rust Programming Language', 12, 1709371800123456),
+            (2, 'https://tokio.rs/', 'Tokio', 0, NULL),
+            (3, 'place:sort=8&maxResults=10', NULL, 0, NULL),
+            (4, 'https://news.ycombinator.com/', 'Hacker News', 3, NULL);
+
+        INSERT INTO moz_bookmarks VALUES
+            (1, 2, NULL, 0, 0, '', 0, 0, 'root________'),
+            (2, 2, NULL, 1, 0, 'menu', 0, 0, 'menu________'),
+            (3, 2, NULL, 1, 1, 'toolbar', 0, 0, 'toolbar_____'),
+            (4, 2, NULL, 1, 2, 'tags', 0, 0, 'tags________'),
+            (5, 2, NULL, 1, 3, 'unfiled', 0, 0, 'unfiled_____'),
+            (6, 2, NULL, 3, 0, '开发', 1709281800000000, 1709281800000000, 'folder000006'),
+            (7, 2, NULL, 6, 0, '异步', 1709281800000000, 1709281800000000, 'folder000007'),
+            (10, 1, 1, 6, 0, NULL, 1709281800000000, 1709368200000000, 'bookmark0010'),
+            (11, 1, 2, 7, 0, '  ', 0, NULL, 'bookmark0011'),
+            (12, 1, 3, 2, 0, '最近使用', 1709281800000000, 0, 'bookmark0012'),
+            (13, 1, 4, 5, 0, 'HN', 1709281800000000, 0, 'bookmark0013'),
+            (20, 2, NULL, 4, 0, 'rust', 0, 0, 'tag000000020'),
+            (21, 2, NULL, 4, 1, '编程', 0, 0, 'tag000000021'),
+            (22, 1, 1, 20, 0, NULL, 0, 0, 'tagitem00022'),
+            (23, 1, 1, 21, 0, NULL, 0, 0, 'tagitem00023'),
+            (24, 1, 2, 20, 0, NULL, 0, 0, 'tagitem00024');
+
+        INSERT INTO moz_keywords VALUES (1, 'rs', 1);";
+
+    // 在临时目录中建出 places.sqlite，返回文件路径
+    fn places(name: &str, sql: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("places-{}-{}.sqlite", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Connection::open(&path).unwrap().execute_batch(sql).unwrap();
+        path
+    }
+
+    fn parse(name: &str, sql: &str) -> Vec<ImportedBookmark> {
+        let path = places(name, sql);
+        let bookmarks = parse_firefox_places(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        bookmarks
+    }
+
+    #[test]
+    fn folder_paths_and_titles() {
+        let bookmarks = parse("paths", SCHEMA);
+        let links: Vec<(&str, &str, String)> = bookmarks
+            .iter()
+            .map(|b| (b.title.as_str(), b.url.as_str(), b.folder_path.join("/")))
+            .collect();
+        // 标签目录下的条目和 place: 查询不是书签
+        assert_eq!(
+            links,
+            [
+                ("HN", "https://news.ycombinator.com/", "Other Bookmarks".to_string()),
+                (
+                    "The Rust Programming Language",
+                    "https://doc.rust-lang.org/book/",
+                    "Bookmarks Toolbar/开发".to_string()
+                ),
+                ("Tokio", "https://tokio.rs/", "Bookmarks Toolbar/开发/异步".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tags_keywords_and_visits() {
+        let bookmarks = parse("tags", SCHEMA);
+        let book = &bookmarks[1];
+        assert_eq!(book.tags, ["rust", "编程"]);
+        assert_eq!(book.keyword.as_deref(), Some("rs"));
+        assert_eq!(book.visit_count, Some(12));
+
+        let tokio = &bookmarks[2];
+        assert_eq!(tokio.tags, ["rust"]);
+        assert_eq!(tokio.keyword, None);
+        assert_eq!(tokio.visit_count, Some(0));
+
+        assert!(bookmarks[0].tags.is_empty());
+    }
+
+    #[test]
+    fn converts_prtime() {
+        let bookmarks = parse("prtime", SCHEMA);
+        let book = &bookmarks[1];
+        assert_eq!(book.created_at.as_deref(), Some("2024-03-01T08:30:00+00:00"));
+        assert_eq!(book.updated_at.as_deref(), Some("2024-03-02T08:30:00+00:00"));
+        assert_eq!(book.last_visited.as_deref(), Some("2024-03-02T09:30:00.123456+00:00"));
+
+        // 0 和 NULL 表示未设置
+        let tokio = &bookmarks[2];
+        assert_eq!(tokio.created_at, None);
+        assert_eq!(tokio.updated_at, None);
+        assert_eq!(tokio.last_visited, None);
+    }
+
+    #[test]
+    fn ignores_keywords_without_place_id() {
+        let sql = SCHEMA.replace(
+            "CREATE TABLE moz_keywords (id INTEGER PRIMARY KEY, keyword TEXT, place_id INTEGER);",
+            "CREATE TABLE moz_keywords (id INTEGER PRIMARY KEY, keyword TEXT);",
+        );
+        let sql = sql.replace("INSERT INTO moz_keywords VALUES (1, 'rs', 1);", "");
+        let bookmarks = parse("old-keywords", &sql);
+        assert_eq!(bookmarks.len(), 3);
+        assert!(bookmarks.iter().all(|b| b.keyword.is_none()));
+    }
+
+    #[test]
+    fn rejects_other_databases() {
+        let path = places("other", "CREATE TABLE bookmarks (id INTEGER PRIMARY KEY);");
+        let error = parse_firefox_places(path.to_str().unwrap()).unwrap_err();
+        assert!(error.to_string().contains("places.sqlite"), "{}", error);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,456 @@
+use super::ImportedBookmark;
+use anyhow::Result;
+use chrono::DateTime;
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::{
+    BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
+};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+// 每次从文件读取的字节数
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// 解析 Netscape 书签 HTML（Chrome、Firefox、Safari、Edge 导出格式）。
+///
+/// 文件结构为嵌套的 `<DL>`，每个 `<DT>` 是一个链接 `<A>` 或文件夹 `<H3>`，
+/// 文件夹内容是紧随其后的 `<DL>`，`<DD>` 是前一项的描述。
+/// 浏览器生成的标签并不闭合，这里不构建 DOM，而是按标签顺序维护一个文件夹栈
+pub fn parse_html_bookmarks(file_path: &str) -> Result<Vec<ImportedBookmark>> {
+    HtmlBookmarkStream::new(BufReader::new(File::open(file_path)?)).collect()
+}
+
+#[cfg(test)]
+pub fn parse_html_str(html: &str) -> Vec<ImportedBookmark> {
+    // 从内存读取不会出错
+    HtmlBookmarkStream::new(html.as_bytes()).filter_map(Result::ok).collect()
+}
+
+/// 边读边解析的书签 HTML，内存占用与文件大小无关
+pub struct HtmlBookmarkStream<R: Read> {
+    reader: R,
+    tokenizer: Tokenizer<Sink>,
+    queue: BufferQueue,
+    // 上一块末尾不完整的 UTF-8 字节
+    pending: Vec<u8>,
+    finished: bool,
+}
+
+impl<R: Read> HtmlBookmarkStream<R> {
+    pub fn new(reader: R) -> Self {
+        HtmlBookmarkStream {
+            reader,
+            tokenizer: Tokenizer::new(Sink::default(), TokenizerOpts::default()),
+            queue: BufferQueue::default(),
+            pending: Vec::new(),
+            finished: false,
+        }
+    }
+
+    fn read_chunk(&mut self) -> Result<()> {
+        let mut chunk = vec![0; CHUNK_SIZE];
+        let read = self.reader.read(&mut chunk)?;
+
+        if read == 0 {
+            if !self.pending.is_empty() {
+                let rest = String::from_utf8_lossy(&self.pending).into_owned();
+                self.pending.clear();
+                self.queue.push_back(StrTendril::from(rest));
+                let _ = self.tokenizer.feed(&mut self.queue);
+            }
+            self.tokenizer.end();
+            self.finished = true;
+            return Ok(());
+        }
+
+        self.pending.extend_from_slice(&chunk[..read]);
+        let complete = complete_utf8_len(&self.pending);
+        let text = String::from_utf8_lossy(&self.pending[..complete]).into_owned();
+        self.pending.drain(..complete);
+
+        self.queue.push_back(StrTendril::from(text));
+        let _ = self.tokenizer.feed(&mut self.queue);
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for HtmlBookmarkStream<R> {
+    type Item = Result<ImportedBookmark>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(bookmark) = self.tokenizer.sink.ready.pop_front() {
+                return Some(Ok(bookmark));
+            }
+            if self.finished {
+                return None;
+            }
+            if let Err(e) = self.read_chunk() {
+                self.finished = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// 去掉末尾被截断的多字节字符后的长度
+fn complete_utf8_len(bytes: &[u8]) -> usize {
+    for back in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - back];
+        if byte & 0xC0 == 0x80 {
+            continue; // 后续字节，继续向前找首字节
+        }
+        let width = match byte {
+            0xF0.. => 4,
+            0xE0.. => 3,
+            0xC0.. => 2,
+            _ => 1,
+        };
+        return if width > back { bytes.len() - back } else { bytes.len() };
+    }
+    bytes.len()
+}
+
+#[derive(Default, PartialEq)]
+enum Capture {
+    #[default]
+    None,
+    Title,
+    Folder,
+    Description,
+}
+
+/// 接收 tokenizer 产生的标签和文本，解析出的书签放入 ready
+#[derive(Default)]
+struct Sink {
+    // 每个打开的 `<DL>` 对应的文件夹路径
+    folders: Vec<Vec<String>>,
+    // 刚读完的 `<H3>`，等待其后的 `<DL>`
+    pending_folder: Option<Vec<String>>,
+    // 最近的链接，等到确定没有 `<DD>` 描述后再输出
+    link: Option<ImportedBookmark>,
+    capture: Capture,
+    text: String,
+    ready: VecDeque<ImportedBookmark>,
+}
+
+impl Sink {
+    fn path(&self) -> Vec<String> {
+        self.folders.last().cloned().unwrap_or_default()
+    }
+
+    fn start_tag(&mut self, tag: &Tag) {
+        match &*tag.name {
+            "dt" => {
+                self.flush();
+                self.pending_folder = None;
+            }
+            "a" => {
+                self.flush();
+                self.link = parse_link(tag, self.path());
+                self.start_capture(Capture::Title);
+            }
+            "h3" => {
+                self.flush();
+                self.start_capture(Capture::Folder);
+            }
+            "dd" => {
+                self.finish_capture();
+                self.start_capture(Capture::Description);
+            }
+            "dl" => {
+                self.flush();
+                // 文件夹内容：属于前一个文件夹，没有则属于当前层级
+                let folder = self.pending_folder.take().unwrap_or_else(|| self.path());
+                self.folders.push(folder);
+            }
+            _ => {}
+        }
+    }
+
+    fn end_tag(&mut self, tag: &Tag) {
+        match &*tag.name {
+            "a" | "h3" | "dd" => self.finish_capture(),
+            "dl" => {
+                self.flush();
+                self.pending_folder = None;
+                self.folders.pop();
+            }
+            _ => {}
+        }
+    }
+
+    fn start_capture(&mut self, capture: Capture) {
+        self.capture = capture;
+        self.text.clear();
+    }
+
+    fn finish_capture(&mut self) {
+        let text = self.text.trim().to_string();
+        match std::mem::take(&mut self.capture) {
+            Capture::Title => {
+                if let Some(link) = &mut self.link {
+                    if !text.is_empty() {
+                        link.title = text;
+                    }
+                }
+            }
+            Capture::Folder => {
+                let mut folder = self.path();
+                if !text.is_empty() {
+                    folder.push(text);
+                }
+                self.pending_folder = Some(folder);
+            }
+            Capture::Description => {
+                if let Some(link) = &mut self.link {
+                    if !text.is_empty() {
+                        link.notes = Some(text);
+                    }
+                }
+            }
+            Capture::None => {}
+        }
+        self.text.clear();
+    }
+
+    fn flush(&mut self) {
+        self.finish_capture();
+        if let Some(link) = self.link.take() {
+            // 不在任何 `<DL>` 中的链接可能是页面上的普通链接，只保留网页地址
+            if !self.folders.is_empty() || link.url.starts_with("http") {
+                self.ready.push_back(link);
+            }
+        }
+    }
+}
+
+impl TokenSink for Sink {
+    type Handle = ();
+
+    fn process_token(&mut self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        match token {
+            Token::TagToken(tag) => match tag.kind {
+                TagKind::StartTag => self.start_tag(&tag),
+                TagKind::EndTag => self.end_tag(&tag),
+            },
+            Token::CharacterTokens(text) if self.capture != Capture::None => self.text.push_str(&text),
+            Token::EOFToken => self.flush(),
+            _ => {}
+        }
+        TokenSinkResult::Continue
+    }
+}
+
+fn parse_link(tag: &Tag, path: Vec<String>) -> Option<ImportedBookmark> {
+    let attr = |name: &str| {
+        tag.attrs
+            .iter()
+            .find(|a| &*a.name.local == name)
+            .map(|a| a.value.trim())
+            .filter(|v| !v.is_empty())
+    };
+
+    let href = attr("href")?;
+    // Firefox 的 place: 是智能书签查询，不是网页
+    if href.starts_with("place:") {
+        return None;
+    }
+
+    let tags = attr("tags")
+        .map(|tags| {
+            tags.split(',')
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(ImportedBookmark {
+        // 标题在读到 `<A>` 的文本后替换
+        title: href.to_string(),
+        url: href.to_string(),
+        folder_path: path,
+        tags,
+        icon_url: attr("icon").or_else(|| attr("icon_uri")).map(str::to_string),
+        keyword: attr("shortcuturl").map(str::to_string),
+        created_at: attr("add_date").and_then(parse_timestamp),
+        updated_at: attr("last_modified").and_then(parse_timestamp),
+        ..Default::default()
+    })
+}
+
+/// 书签文件中的时间戳通常是 Unix 秒，个别工具导出毫秒或微秒
+fn parse_timestamp(value: &str) -> Option<String> {
+    let value: i64 = value.trim().parse().ok()?;
+    let seconds = if value > 100_000_000_000_000 {
+        value / 1_000_000
+    } else if value > 100_000_000_000 {
+        value / 1_000
+    } else {
+        value
+    };
+    if seconds <= 0 {
+        return None;
+    }
+    DateTime::from_timestamp(seconds, 0).map(|dt| dt.to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHROME: &str = include_str!("../../tests/fixtures/chrome_bookmarks.html");
+    const FIREFOX: &str = include_str!("../../tests/fixtures/firefox_bookmarks.html");
+    const SAFARI: &str = include_str!("../../tests/fixtures/safari_bookmarks.html");
+    const EDGE: &str = include_str!("../../tests/fixtures/edge_bookmarks.html");
+
+    // 每个链接的 (标题, 网址, 文件夹路径)
+    fn links(bookmarks: &[ImportedBookmark]) -> Vec<(&str, &str, String)> {
+        bookmarks
+            .iter()
+            .map(|b| (b.title.as_str(), b.url.as_str(), b.folder_path.join("/")))
+            .collect()
+    }
+
+    fn find<'a>(bookmarks: &'a [ImportedBookmark], url: &str) -> &'a ImportedBookmark {
+        bookmarks.iter().find(|b| b.url == url).unwrap()
+    }
+
+    #[test]
+    fn chrome_export() {
+        let bookmarks = parse_html_str(CHROME);
+        assert_eq!(
+            links(&bookmarks),
+            [
+                ("Rust", "https://www.rust-lang.org/", "Bookmarks bar".to_string()),
+                ("The Rust Programming Language", "https://doc.rust-lang.org/book/", "Bookmarks bar/开发".to_string()),
+                ("GitHub", "https://github.com/", "Bookmarks bar/开发/工具".to_string()),
+                ("crates.io", "https://crates.io/", "Bookmarks bar/开发".to_string()),
+                ("每日签到 ✅", "https://example.com/checkin", "Other bookmarks".to_string()),
+            ]
+        );
+
+        let rust = find(&bookmarks, "https://www.rust-lang.org/");
+        assert_eq!(rust.icon_url.as_deref(), Some("data:image/png;base64,iVBORw0KGgo="));
+        assert_eq!(rust.created_at.as_deref(), Some("2023-11-14T22:13:21+00:00"));
+        assert_eq!(rust.updated_at, None);
+    }
+
+    #[test]
+    fn firefox_export() {
+        let bookmarks = parse_html_str(FIREFOX);
+        // place: 查询不是网页，不导入
+        assert_eq!(
+            links(&bookmarks),
+            [
+                ("Mozilla", "https://www.mozilla.org/", String::new()),
+                ("Rust Blog", "https://blog.rust-lang.org/", "阅读".to_string()),
+                ("MDN Web Docs", "https://developer.mozilla.org/", "Bookmarks Toolbar".to_string()),
+            ]
+        );
+
+        let mozilla = find(&bookmarks, "https://www.mozilla.org/");
+        assert_eq!(mozilla.tags, ["mozilla", "browser"]);
+        assert_eq!(mozilla.notes.as_deref(), Some("浏览器厂商"));
+        assert_eq!(mozilla.icon_url.as_deref(), Some("https://www.mozilla.org/favicon.ico"));
+        assert_eq!(mozilla.created_at.as_deref(), Some("2023-11-14T22:13:21+00:00"));
+        assert_eq!(mozilla.updated_at.as_deref(), Some("2023-11-14T22:21:40+00:00"));
+
+        let blog = find(&bookmarks, "https://blog.rust-lang.org/");
+        assert_eq!(blog.tags, ["rust"]);
+        assert_eq!(blog.notes.as_deref(), Some("官方博客 & 公告"));
+        assert_eq!(blog.keyword.as_deref(), Some("rb"));
+        assert_eq!(blog.icon_url.as_deref(), Some("data:image/png;base64,AAAA"));
+        assert_eq!(blog.created_at.as_deref(), Some("2023-11-14T22:15:00+00:00"));
+        assert_eq!(blog.updated_at.as_deref(), Some("2023-11-14T22:16:40+00:00"));
+
+        // 文件夹的 <DD> 描述不会落到链接上
+        assert_eq!(find(&bookmarks, "https://developer.mozilla.org/").notes, None);
+    }
+
+    #[test]
+    fn safari_export() {
+        // Safari 的顶层文件夹不在 <DL> 中，也没有时间戳
+        let bookmarks = parse_html_str(SAFARI);
+        assert_eq!(
+            links(&bookmarks),
+            [
+                ("Apple", "https://www.apple.com/", "Favorites".to_string()),
+                ("Apple Developer", "https://developer.apple.com/", "开发".to_string()),
+                ("Swift.org", "https://www.swift.org/", "开发/Swift".to_string()),
+                ("WebKit Blog", "https://webkit.org/blog/", "Reading List".to_string()),
+            ]
+        );
+        let webkit = find(&bookmarks, "https://webkit.org/blog/");
+        assert_eq!(webkit.notes.as_deref(), Some("Safari 和 WebKit 的新功能"));
+        assert_eq!(webkit.created_at, None);
+    }
+
+    #[test]
+    fn edge_export() {
+        let bookmarks = parse_html_str(EDGE);
+        assert_eq!(
+            links(&bookmarks),
+            [
+                ("Bing", "https://www.bing.com/", "Favorites bar".to_string()),
+                ("Microsoft Learn", "https://learn.microsoft.com/zh-cn/", "Favorites bar/工作".to_string()),
+                ("Outlook", "https://outlook.live.com/", "Other favorites".to_string()),
+            ]
+        );
+        let learn = find(&bookmarks, "https://learn.microsoft.com/zh-cn/");
+        assert_eq!(learn.created_at.as_deref(), Some("2023-11-14T22:13:24+00:00"));
+    }
+
+    #[test]
+    fn timestamps_in_milliseconds_and_microseconds() {
+        assert_eq!(parse_timestamp("1700000001").as_deref(), Some("2023-11-14T22:13:21+00:00"));
+        assert_eq!(parse_timestamp("1700000001000").as_deref(), Some("2023-11-14T22:13:21+00:00"));
+        assert_eq!(parse_timestamp("1700000001000000").as_deref(), Some("2023-11-14T22:13:21+00:00"));
+        assert_eq!(parse_timestamp("0"), None);
+        assert_eq!(parse_timestamp("abc"), None);
+    }
+
+    #[test]
+    fn complete_utf8_len_stops_before_truncated_character() {
+        let text = "a中😀";
+        let bytes = text.as_bytes();
+        assert_eq!(complete_utf8_len(bytes), bytes.len());
+        assert_eq!(complete_utf8_len(&bytes[..1]), 1);
+        // “中” 三个字节被截断
+        assert_eq!(complete_utf8_len(&bytes[..2]), 1);
+        assert_eq!(complete_utf8_len(&bytes[..3]), 1);
+        assert_eq!(complete_utf8_len(&bytes[..4]), 4);
+        // 😀 四个字节被截断
+        for end in 5..8 {
+            assert_eq!(complete_utf8_len(&bytes[..end]), 4);
+        }
+        assert_eq!(complete_utf8_len(&[]), 0);
+    }
+
+    // 每次最多读 limit 个字节，让多字节字符落在两次读取之间
+    struct Trickle<'a> {
+        data: &'a [u8],
+        limit: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.limit.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn multibyte_characters_across_chunks() {
+        let expected = parse_html_str(CHROME);
+        for limit in 1..=4 {
+            let stream = HtmlBookmarkStream::new(Trickle { data: CHROME.as_bytes(), limit });
+            let bookmarks = stream.collect::<Result<Vec<_>>>().unwrap();
+            assert_eq!(links(&bookmarks), links(&expected), "limit {}", limit);
+        }
+    }
+}
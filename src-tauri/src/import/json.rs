@@ -0,0 +1,125 @@
+use super::ImportedBookmark;
+use crate::models::BookmarkBackup;
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::fs;
+
+/// 读取 export::write_json 生成的备份。书签按 folder_path 重建文件夹，原来的 id 不保留
+pub fn parse_json_backup(file_path: &str) -> Result<Vec<ImportedBookmark>> {
+    let json = fs::read_to_string(file_path)?;
+    parse_json_str(&json)
+}
+
+pub fn parse_json_str(json: &str) -> Result<Vec<ImportedBookmark>> {
+    let mut value: Value = serde_json::from_str(json).context("不是有效的书签备份文件")?;
+    upgrade_v1(&mut value);
+    let backup: BookmarkBackup = serde_json::from_value(value).context("不是有效的书签备份文件")?;
+    if backup.format != BookmarkBackup::FORMAT {
+        bail!("不是有效的书签备份文件");
+    }
+    if backup.version > BookmarkBackup::VERSION {
+        bail!(
+            "备份文件版本 {} 高于当前支持的版本 {}，请先升级应用",
+            backup.version,
+            BookmarkBackup::VERSION
+        );
+    }
+
+    let bookmarks = backup
+        .bookmarks
+        .into_iter()
+        .map(|bookmark| ImportedBookmark {
+            folder_path: super::split_folder_path(bookmark.folder_path.as_deref().unwrap_or_default()),
+            title: bookmark.title,
+            url: bookmark.url,
+            tags: bookmark.tags,
+            notes: bookmark.notes,
+            icon_url: bookmark.icon_url,
+            keyword: bookmark.keyword,
+            reminders: bookmark.reminders,
+            exclude_from_digest: bookmark.exclude_from_digest,
+            visit_count: Some(bookmark.visit_count),
+            last_visited: bookmark.last_visited,
+            created_at: Some(bookmark.created_at),
+            updated_at: Some(bookmark.updated_at),
+        })
+        .collect();
+
+    Ok(bookmarks)
+}
+
+// 版本 1 的书签只有一个 reminder 对象，改成版本 2 的 reminders 数组
+fn upgrade_v1(backup: &mut Value) {
+    if backup["version"] != 1 {
+        return;
+    }
+    let Some(bookmarks) = backup["bookmarks"].as_array_mut() else {
+        return;
+    };
+    for bookmark in bookmarks.iter_mut().filter_map(Value::as_object_mut) {
+        let reminders = match bookmark.remove("reminder") {
+            Some(Value::Null) | None => Vec::new(),
+            Some(reminder) => vec![reminder],
+        };
+        bookmark.insert("reminders".to_string(), Value::Array(reminders));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ReminderFrequency;
+
+    #[test]
+    fn upgrades_version_1_reminder() {
+        let json = r#"{
+            "format": "bookmark-manager",
+            "version": 1,
+            "exported_at": "2024-03-01T08:30:00+00:00",
+            "bookmarks": [
+                {
+                    "id": 1, "title": "Rust", "url": "https://www.rust-lang.org/", "folder_id": 2,
+                    "folder_path": "开发/Rust", "tags": ["rust"], "icon_url": null, "notes": null, "keyword": null,
+                    "reminder": {"enabled": true, "frequency": {"type": "daily"}, "time": "09:00", "days": [],
+                                 "last_reminded": null, "next_reminder": null},
+                    "visit_count": 3, "last_visited": null,
+                    "created_at": "2024-03-01T08:30:00+00:00", "updated_at": "2024-03-02T08:30:00+00:00"
+                },
+                {
+                    "id": 2, "title": "No reminder", "url": "https://example.com/", "folder_id": null,
+                    "folder_path": null, "tags": [], "icon_url": null, "notes": null, "keyword": null,
+                    "reminder": null, "visit_count": 0, "last_visited": null,
+                    "created_at": "2024-03-01T08:30:00+00:00", "updated_at": "2024-03-01T08:30:00+00:00"
+                }
+            ]
+        }"#;
+        let bookmarks = parse_json_str(json).unwrap();
+        assert_eq!(bookmarks.len(), 2);
+
+        let rust = &bookmarks[0];
+        assert_eq!(rust.folder_path, ["开发", "Rust"]);
+        assert_eq!(rust.reminders.len(), 1);
+        assert_eq!(rust.reminders[0].frequency, ReminderFrequency::Daily);
+        assert_eq!(rust.reminders[0].time, "09:00");
+        assert_eq!(rust.visit_count, Some(3));
+        assert_eq!(rust.updated_at.as_deref(), Some("2024-03-02T08:30:00+00:00"));
+
+        assert!(bookmarks[1].reminders.is_empty());
+        assert!(bookmarks[1].folder_path.is_empty());
+    }
+
+    #[test]
+    fn rejects_newer_version() {
+        let json = r#"{"format": "bookmark-manager", "version": 3, "exported_at": "", "bookmarks": []}"#;
+        let error = parse_json_str(json).unwrap_err().to_string();
+        assert!(error.contains("版本 3"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let json = r#"{"format": "something-else", "version": 2, "exported_at": "", "bookmarks": []}"#;
+        assert!(parse_json_str(json).is_err());
+        assert!(parse_json_str(r#"{"roots": {}}"#).is_err());
+        assert!(parse_json_str("not json").is_err());
+    }
+}
@@ -0,0 +1,277 @@
+mod chromium;
+mod csv;
+mod firefox;
+mod html;
+mod json;
+mod pinboard;
+mod pocket;
+mod raindrop;
+
+pub use chromium::parse_chromium_bookmarks;
+pub use self::csv::parse_csv_bookmarks;
+pub use firefox::parse_firefox_places;
+pub use html::{parse_html_bookmarks, HtmlBookmarkStream};
+pub use json::parse_json_backup;
+pub use pinboard::parse_pinboard_json;
+pub use pocket::parse_pocket_export;
+pub use raindrop::parse_raindrop_csv;
+
+use crate::models::{ImportFormat, Reminder};
+use anyhow::Result;
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+
+// 稍后阅读服务中未读（Pinboard 的 toread、Pocket 的未读列表）的书签加上这个标签
+const UNREAD_TAG: &str = "unread";
+
+/// 各种来源解析出的书签，统一交给 Database::import_stream 处理
+#[derive(Debug, Clone, Default)]
+pub struct ImportedBookmark {
+    pub title: String,
+    pub url: String,
+    pub folder_path: Vec<String>,
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+    pub icon_url: Option<String>,
+    pub keyword: Option<String>,
+    pub reminders: Vec<Reminder>,
+    pub exclude_from_digest: bool,
+    pub visit_count: Option<i64>,
+    pub last_visited: Option<String>, // RFC 3339
+    pub created_at: Option<String>,   // RFC 3339
+    pub updated_at: Option<String>,   // RFC 3339
+}
+
+/// 按格式解析书签文件
+pub fn parse_file(file_path: &str, format: ImportFormat) -> Result<Vec<ImportedBookmark>> {
+    match format {
+        ImportFormat::Html => parse_html_bookmarks(file_path),
+        ImportFormat::FirefoxPlaces => parse_firefox_places(file_path),
+        ImportFormat::Chromium => parse_chromium_bookmarks(file_path),
+        ImportFormat::Json => parse_json_backup(file_path),
+        ImportFormat::Csv => parse_csv_bookmarks(file_path),
+        ImportFormat::Pinboard => parse_pinboard_json(file_path),
+        ImportFormat::Pocket => parse_pocket_export(file_path),
+        ImportFormat::Raindrop => parse_raindrop_csv(file_path),
+    }
+}
+
+pub type BookmarkStream = Box<dyn Iterator<Item = Result<ImportedBookmark>>>;
+
+/// 打开的导入文件，书签在迭代时才解析
+pub struct ImportSource {
+    pub items: BookmarkStream,
+    /// 书签总数，边读边解析时未知
+    pub total: Option<usize>,
+    /// 已读取的字节数，与 total_bytes 一起用于估算进度
+    pub bytes_read: Arc<AtomicU64>,
+    pub total_bytes: u64,
+}
+
+/// 打开书签文件用于导入。HTML 边读边解析，适合很大的文件；其余格式先整体解析
+pub fn open_source(file_path: &str, format: ImportFormat) -> Result<ImportSource> {
+    let bytes_read = Arc::new(AtomicU64::new(0));
+
+    if let ImportFormat::Html = format {
+        let file = File::open(file_path)?;
+        let total_bytes = file.metadata()?.len();
+        let reader = CountingReader {
+            inner: BufReader::new(file),
+            count: bytes_read.clone(),
+        };
+        return Ok(ImportSource {
+            items: Box::new(HtmlBookmarkStream::new(reader)),
+            total: None,
+            bytes_read,
+            total_bytes,
+        });
+    }
+
+    let items = parse_file(file_path, format)?;
+    // Chromium 可以传入目录，此时没有文件大小
+    let total_bytes = fs::metadata(file_path).map(|m| if m.is_file() { m.len() } else { 0 }).unwrap_or(0);
+    bytes_read.store(total_bytes, Ordering::Relaxed);
+    Ok(ImportSource {
+        total: Some(items.len()),
+        items: Box::new(items.into_iter().map(Ok)),
+        bytes_read,
+        total_bytes,
+    })
+}
+
+/// 统计已读取的字节数
+struct CountingReader<R> {
+    inner: R,
+    count: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count.fetch_add(read as u64, Ordering::Relaxed);
+        Ok(read)
+    }
+}
+
+/// 根据文件内容判断格式：目录按 Chromium 配置目录处理，SQLite 文件是 Firefox，
+/// JSON 和 CSV 按顶层结构和表头区分来源
+pub fn detect_format(file_path: &str) -> Result<ImportFormat> {
+    if Path::new(file_path).is_dir() {
+        return Ok(ImportFormat::Chromium);
+    }
+
+    let mut head = Vec::new();
+    File::open(file_path)?.take(4096).read_to_end(&mut head)?;
+    if head.starts_with(b"SQLite format 3\0") {
+        return Ok(ImportFormat::FirefoxPlaces);
+    }
+
+    let text = String::from_utf8_lossy(&head);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    let first_line = text.lines().next().unwrap_or_default().to_ascii_lowercase();
+    let format = if text.starts_with('<') {
+        if text.contains("<title>Pocket Export</title>") {
+            ImportFormat::Pocket
+        } else {
+            ImportFormat::Html
+        }
+    } else if text.starts_with('[') {
+        ImportFormat::Pinboard
+    } else if text.starts_with('{') {
+        if text.contains("\"roots\"") {
+            ImportFormat::Chromium
+        } else {
+            ImportFormat::Json
+        }
+    } else if first_line.contains("time_added") {
+        ImportFormat::Pocket
+    } else if first_line.contains("excerpt") && first_line.contains("created") {
+        ImportFormat::Raindrop
+    } else {
+        ImportFormat::Csv
+    };
+    Ok(format)
+}
+
+/// 把 "工作/项目" 形式的文件夹路径拆成各级名称
+fn split_folder_path(path: &str) -> Vec<String> {
+    path.split('/')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 把 RFC 3339 时间统一转换成 UTC，无法解析时返回 None
+fn normalize_time(value: &str) -> Option<String> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .ok()
+        .map(|time| time.with_timezone(&Utc).to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Bookmark, CatchUpPolicy, CsvColumn, ReminderFrequency};
+
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    #[test]
+    fn detects_read_later_exports() {
+        assert!(matches!(detect_format(&fixture("pinboard_export.json")).unwrap(), ImportFormat::Pinboard));
+        assert!(matches!(detect_format(&fixture("pocket_export.html")).unwrap(), ImportFormat::Pocket));
+        assert!(matches!(detect_format(&fixture("pocket_export.csv")).unwrap(), ImportFormat::Pocket));
+        assert!(matches!(detect_format(&fixture("raindrop_export.csv")).unwrap(), ImportFormat::Raindrop));
+    }
+
+    #[test]
+    fn detects_browser_html_exports() {
+        for name in ["chrome_bookmarks.html", "firefox_bookmarks.html", "safari_bookmarks.html", "edge_bookmarks.html"] {
+            assert!(matches!(detect_format(&fixture(name)).unwrap(), ImportFormat::Html), "{}", name);
+        }
+    }
+
+    #[test]
+    fn parse_file_uses_format() {
+        let bookmarks = parse_file(&fixture("pocket_export.csv"), ImportFormat::Pocket).unwrap();
+        assert_eq!(bookmarks.len(), 3);
+        let bookmarks = parse_file(&fixture("raindrop_export.csv"), ImportFormat::Raindrop).unwrap();
+        assert_eq!(bookmarks.len(), 3);
+    }
+
+    fn sample_bookmark() -> Bookmark {
+        Bookmark {
+            id: Some(7),
+            title: "Rust, \"官方\"".into(),
+            url: "https://www.rust-lang.org/".into(),
+            folder_id: Some(3),
+            folder_path: Some("开发/Rust".into()),
+            tags: vec!["rust".into(), "编程".into()],
+            icon_url: Some("data:image/png;base64,iVBORw0KGgo=".into()),
+            notes: Some("第一行\n第二行".into()),
+            keyword: Some("rs".into()),
+            reminders: vec![Reminder {
+                id: Some(11),
+                enabled: true,
+                frequency: ReminderFrequency::Weekly,
+                time: "09:00".into(),
+                days: vec![1, 3],
+                last_reminded: Some("2024-03-04T09:00:00+00:00".into()),
+                next_reminder: Some("2024-03-06T09:00:00+00:00".into()),
+                catch_up: CatchUpPolicy::Skip,
+                timezone: Some("Asia/Shanghai".into()),
+                title_template: Some("{title}".into()),
+                message_template: None,
+            }],
+            exclude_from_digest: true,
+            visit_count: 5,
+            last_visited: Some("2024-03-05T10:00:00+00:00".into()),
+            created_at: "2024-03-01T08:30:00+00:00".into(),
+            updated_at: "2024-03-02T08:30:00+00:00".into(),
+        }
+    }
+
+    fn assert_round_trip(original: &Bookmark, imported: &ImportedBookmark) {
+        assert_eq!(imported.title, original.title);
+        assert_eq!(imported.url, original.url);
+        assert_eq!(imported.folder_path, ["开发", "Rust"]);
+        assert_eq!(imported.tags, original.tags);
+        assert_eq!(imported.notes, original.notes);
+        assert_eq!(imported.icon_url, original.icon_url);
+        assert_eq!(imported.keyword, original.keyword);
+        assert_eq!(
+            serde_json::to_value(&imported.reminders).unwrap(),
+            serde_json::to_value(&original.reminders).unwrap()
+        );
+        assert_eq!(imported.visit_count, Some(original.visit_count));
+        assert_eq!(imported.last_visited, original.last_visited);
+        assert_eq!(imported.created_at.as_deref(), Some(original.created_at.as_str()));
+        assert_eq!(imported.updated_at.as_deref(), Some(original.updated_at.as_str()));
+    }
+
+    #[test]
+    fn json_backup_round_trip() {
+        let original = sample_bookmark();
+        let json = crate::export::write_json(std::slice::from_ref(&original)).unwrap();
+        let imported = json::parse_json_str(&json).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_round_trip(&original, &imported[0]);
+        assert!(imported[0].exclude_from_digest);
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let original = sample_bookmark();
+        let text = crate::export::write_csv(std::slice::from_ref(&original), &CsvColumn::ALL).unwrap();
+        let imported = self::csv::parse_csv_str(&text).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_round_trip(&original, &imported[0]);
+    }
+}
+
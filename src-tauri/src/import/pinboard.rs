@@ -0,0 +1,101 @@
+use super::ImportedBookmark;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+
+#[derive(Deserialize)]
+struct Post {
+    href: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    extended: String,
+    #[serde(default)]
+    tags: String,
+    time: Option<String>,
+    #[serde(default)]
+    toread: String,
+}
+
+/// 解析 Pinboard 的 JSON 导出（设置 → 备份 → JSON）。
+///
+/// description 是标题，extended 是备注，标签以空格分隔，toread 为 yes 时加上待读标签
+pub fn parse_pinboard_json(file_path: &str) -> Result<Vec<ImportedBookmark>> {
+    let json = fs::read_to_string(file_path)?;
+    parse_pinboard_str(&json)
+}
+
+pub fn parse_pinboard_str(json: &str) -> Result<Vec<ImportedBookmark>> {
+    let posts: Vec<Post> = serde_json::from_str(json).context("不是有效的 Pinboard 导出文件")?;
+
+    let bookmarks = posts
+        .into_iter()
+        .map(|post| {
+            let mut tags: Vec<String> = post.tags.split_whitespace().map(str::to_string).collect();
+            if post.toread == "yes" {
+                tags.push(super::UNREAD_TAG.to_string());
+            }
+            let created_at = post.time.as_deref().and_then(super::normalize_time);
+
+            ImportedBookmark {
+                title: post.description.trim().to_string(),
+                url: post.href.trim().to_string(),
+                tags,
+                notes: Some(post.extended.trim().to_string()).filter(|n| !n.is_empty()),
+                updated_at: created_at.clone(),
+                created_at,
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    Ok(bookmarks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::UNREAD_TAG;
+
+    const EXPORT: &str = include_str!("../../tests/fixtures/pinboard_export.json");
+
+    #[test]
+    fn maps_posts() {
+        let bookmarks = parse_pinboard_str(EXPORT).unwrap();
+        assert_eq!(bookmarks.len(), 3);
+
+        let rust = &bookmarks[0];
+        assert_eq!(rust.title, "Rust Programming Language");
+        assert_eq!(rust.url, "https://www.rust-lang.org/");
+        assert_eq!(rust.tags, ["rust", "编程"]);
+        assert_eq!(rust.notes.as_deref(), Some("官方网站"));
+        assert_eq!(rust.created_at.as_deref(), Some("2024-03-01T08:30:00+00:00"));
+        assert_eq!(rust.updated_at, rust.created_at);
+    }
+
+    #[test]
+    fn toread_adds_unread_tag() {
+        let bookmarks = parse_pinboard_str(EXPORT).unwrap();
+        let long_read = &bookmarks[1];
+        assert_eq!(long_read.title, "A long read");
+        assert_eq!(long_read.tags, [UNREAD_TAG]);
+        assert_eq!(long_read.notes, None);
+        // 带时区的时间转换成 UTC
+        assert_eq!(long_read.created_at.as_deref(), Some("2024-03-02T13:15:00+00:00"));
+    }
+
+    #[test]
+    fn missing_fields() {
+        let bookmarks = parse_pinboard_str(EXPORT).unwrap();
+        let no_time = &bookmarks[2];
+        assert_eq!(no_time.tags, ["misc"]);
+        assert_eq!(no_time.notes, None);
+        assert_eq!(no_time.created_at, None);
+        assert_eq!(no_time.updated_at, None);
+    }
+
+    #[test]
+    fn rejects_other_json() {
+        assert!(parse_pinboard_str(r#"{"roots": {}}"#).is_err());
+    }
+}
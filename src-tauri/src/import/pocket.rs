@@ -0,0 +1,164 @@
+use super::ImportedBookmark;
+use anyhow::{bail, Result};
+use chrono::DateTime;
+use scraper::{ElementRef, Html};
+use std::fs;
+
+/// 解析 Pocket 的导出文件，支持旧版的 ril_export.html 和新版的 CSV。
+///
+/// 未读列表中的条目加上待读标签，已归档的不加
+pub fn parse_pocket_export(file_path: &str) -> Result<Vec<ImportedBookmark>> {
+    let text = fs::read_to_string(file_path)?;
+    if text.trim_start().starts_with('<') {
+        Ok(parse_pocket_html(&text))
+    } else {
+        parse_pocket_csv(&text)
+    }
+}
+
+/// HTML 导出由若干 `<h1>` 分节（Unread / Read Archive），每节后是一个 `<ul>` 链接列表，
+/// 链接带有 time_added（Unix 秒）和 tags（逗号分隔）属性
+pub fn parse_pocket_html(html: &str) -> Vec<ImportedBookmark> {
+    let document = Html::parse_document(html);
+    let mut bookmarks = Vec::new();
+    let mut unread = false;
+
+    for element in document.root_element().descendants().filter_map(ElementRef::wrap) {
+        match element.value().name() {
+            "h1" => unread = element.text().collect::<String>().trim().eq_ignore_ascii_case("unread"),
+            "a" => {
+                let attrs = element.value();
+                let href = match attrs.attr("href").map(str::trim).filter(|h| !h.is_empty()) {
+                    Some(href) => href,
+                    None => continue,
+                };
+                let created_at = attrs.attr("time_added").and_then(from_unix_seconds);
+
+                bookmarks.push(ImportedBookmark {
+                    title: element.text().collect::<String>().trim().to_string(),
+                    url: href.to_string(),
+                    tags: split_tags(attrs.attr("tags").unwrap_or_default(), ',', unread),
+                    updated_at: created_at.clone(),
+                    created_at,
+                    ..Default::default()
+                });
+            }
+            _ => {}
+        }
+    }
+
+    bookmarks
+}
+
+/// CSV 导出的列为 title,url,time_added,tags,status，标签以 | 分隔，status 为 unread 或 archive
+pub fn parse_pocket_csv(text: &str) -> Result<Vec<ImportedBookmark>> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(text.as_bytes());
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| headers.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+    let url_column = match column("url") {
+        Some(index) => index,
+        None => bail!("不是有效的 Pocket 导出文件"),
+    };
+    let (title_column, time_column, tags_column, status_column) =
+        (column("title"), column("time_added"), column("tags"), column("status"));
+
+    let mut bookmarks = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let field = |index: Option<usize>| index.and_then(|i| record.get(i)).unwrap_or_default().trim();
+
+        let unread = field(status_column).eq_ignore_ascii_case("unread");
+        let created_at = from_unix_seconds(field(time_column));
+        bookmarks.push(ImportedBookmark {
+            title: field(title_column).to_string(),
+            url: field(Some(url_column)).to_string(),
+            tags: split_tags(field(tags_column), '|', unread),
+            updated_at: created_at.clone(),
+            created_at,
+            ..Default::default()
+        });
+    }
+
+    Ok(bookmarks)
+}
+
+fn split_tags(value: &str, separator: char, unread: bool) -> Vec<String> {
+    let mut tags: Vec<String> = value
+        .split(separator)
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect();
+    if unread {
+        tags.push(super::UNREAD_TAG.to_string());
+    }
+    tags
+}
+
+fn from_unix_seconds(value: &str) -> Option<String> {
+    let seconds: i64 = value.trim().parse().ok()?;
+    if seconds <= 0 {
+        return None;
+    }
+    DateTime::from_timestamp(seconds, 0).map(|dt| dt.to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::UNREAD_TAG;
+
+    const HTML_EXPORT: &str = include_str!("../../tests/fixtures/pocket_export.html");
+    const CSV_EXPORT: &str = include_str!("../../tests/fixtures/pocket_export.csv");
+
+    fn find<'a>(bookmarks: &'a [ImportedBookmark], url: &str) -> &'a ImportedBookmark {
+        bookmarks.iter().find(|b| b.url == url).unwrap()
+    }
+
+    #[test]
+    fn html_export() {
+        let bookmarks = parse_pocket_html(HTML_EXPORT);
+        assert_eq!(bookmarks.len(), 4);
+
+        let article = find(&bookmarks, "https://example.com/article");
+        assert_eq!(article.title, "An article");
+        assert_eq!(article.tags, ["reading", "技术", UNREAD_TAG]);
+        assert_eq!(article.created_at.as_deref(), Some("2024-03-01T08:30:00+00:00"));
+        assert_eq!(article.updated_at, article.created_at);
+
+        assert_eq!(find(&bookmarks, "https://example.com/untagged").tags, [UNREAD_TAG]);
+
+        // 归档中的条目不加待读标签
+        let archived = find(&bookmarks, "https://example.com/archived");
+        assert_eq!(archived.tags, ["done"]);
+        assert_eq!(archived.created_at.as_deref(), Some("2024-02-01T00:00:00+00:00"));
+
+        let no_time = find(&bookmarks, "https://example.com/no-time");
+        assert!(no_time.tags.is_empty());
+        assert_eq!(no_time.created_at, None);
+    }
+
+    #[test]
+    fn csv_export() {
+        let bookmarks = parse_pocket_csv(CSV_EXPORT).unwrap();
+        assert_eq!(bookmarks.len(), 3);
+
+        let article = find(&bookmarks, "https://example.com/article");
+        assert_eq!(article.title, "An article");
+        assert_eq!(article.tags, ["reading", "技术", UNREAD_TAG]);
+        assert_eq!(article.created_at.as_deref(), Some("2024-03-01T08:30:00+00:00"));
+
+        let archived = find(&bookmarks, "https://example.com/archived");
+        assert_eq!(archived.title, "Archived, with comma");
+        assert_eq!(archived.tags, ["done"]);
+
+        let no_time = find(&bookmarks, "https://example.com/no-time");
+        assert_eq!(no_time.tags, [UNREAD_TAG]);
+        assert_eq!(no_time.created_at, None);
+    }
+
+    #[test]
+    fn csv_without_url_column() {
+        assert!(parse_pocket_csv("title,time_added\nA,1709281800\n").is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use super::ImportedBookmark;
+use anyhow::{bail, Result};
+use std::fs;
+
+// Raindrop 中没有放进任何收藏集的书签
+const UNSORTED_FOLDER: &str = "Unsorted";
+
+/// 解析 Raindrop.io 的 CSV 导出。
+///
+/// 列为 id,title,note,excerpt,url,folder,tags,created,cover,highlights,favorite：
+/// folder 是收藏集路径，tags 以逗号分隔，note 和 excerpt 合并为备注
+pub fn parse_raindrop_csv(file_path: &str) -> Result<Vec<ImportedBookmark>> {
+    let text = fs::read_to_string(file_path)?;
+    parse_raindrop_str(&text)
+}
+
+pub fn parse_raindrop_str(text: &str) -> Result<Vec<ImportedBookmark>> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(text.as_bytes());
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| headers.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+    let url_column = match column("url") {
+        Some(index) => index,
+        None => bail!("不是有效的 Raindrop 导出文件"),
+    };
+    let (title_column, note_column, excerpt_column, folder_column, tags_column, created_column) = (
+        column("title"),
+        column("note"),
+        column("excerpt"),
+        column("folder"),
+        column("tags"),
+        column("created"),
+    );
+
+    let mut bookmarks = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let field = |index: Option<usize>| index.and_then(|i| record.get(i)).unwrap_or_default().trim();
+
+        let notes: Vec<&str> = [field(note_column), field(excerpt_column)]
+            .into_iter()
+            .filter(|n| !n.is_empty())
+            .collect();
+        let folder = field(folder_column);
+        let created_at = super::normalize_time(field(created_column));
+
+        bookmarks.push(ImportedBookmark {
+            title: field(title_column).to_string(),
+            url: field(Some(url_column)).to_string(),
+            folder_path: if folder.eq_ignore_ascii_case(UNSORTED_FOLDER) {
+                Vec::new()
+            } else {
+                super::split_folder_path(folder)
+            },
+            tags: field(tags_column)
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect(),
+            notes: if notes.is_empty() { None } else { Some(notes.join("\n\n")) },
+            updated_at: created_at.clone(),
+            created_at,
+            ..Default::default()
+        });
+    }
+
+    Ok(bookmarks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = include_str!("../../tests/fixtures/raindrop_export.csv");
+
+    #[test]
+    fn maps_columns() {
+        let bookmarks = parse_raindrop_str(EXPORT).unwrap();
+        assert_eq!(bookmarks.len(), 3);
+
+        let rust = &bookmarks[0];
+        assert_eq!(rust.title, "Rust");
+        assert_eq!(rust.url, "https://www.rust-lang.org/");
+        assert_eq!(rust.folder_path, ["开发", "Rust"]);
+        assert_eq!(rust.tags, ["rust", "编程"]);
+        assert_eq!(rust.notes.as_deref(), Some("My note\n\nThe Rust programming language"));
+        assert_eq!(rust.created_at.as_deref(), Some("2024-03-01T08:30:00+00:00"));
+        assert_eq!(rust.updated_at, rust.created_at);
+    }
+
+    #[test]
+    fn unsorted_goes_to_root() {
+        let bookmarks = parse_raindrop_str(EXPORT).unwrap();
+        let unsorted = &bookmarks[1];
+        assert!(unsorted.folder_path.is_empty());
+        assert!(unsorted.tags.is_empty());
+        assert_eq!(unsorted.notes, None);
+        assert_eq!(unsorted.created_at.as_deref(), Some("2024-03-02T13:15:00+00:00"));
+    }
+
+    #[test]
+    fn excerpt_only() {
+        let bookmarks = parse_raindrop_str(EXPORT).unwrap();
+        let excerpt = &bookmarks[2];
+        assert_eq!(excerpt.folder_path, ["Reading"]);
+        assert_eq!(excerpt.notes.as_deref(), Some("Just an excerpt, with a comma"));
+        assert_eq!(excerpt.created_at, None);
+    }
+}
@@ -1,12 +1,21 @@
+mod canonical_url;
 mod commands;
 mod database;
+mod export;
+mod migrations;
 mod models;
+mod notification;
+mod query;
+mod quiet_hours;
 mod reminder;
+mod rrule;
+mod schedule;
+mod settings;
 mod import;
 
-use database::Database;
 use reminder::ReminderService;
 use tauri::Manager;
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -19,9 +28,15 @@ pub fn run() {
             let app_dir = app.path().app_data_dir().expect("Failed to get app data dir");
             std::fs::create_dir_all(&app_dir).expect("Failed to create app data directory");
 
-            let db_path = app_dir.join("bookmarks.db");
-            let db = Database::new(db_path.to_str().unwrap())
-                .expect("Failed to initialize database");
+            // 自定义位置的数据库打不开时改用默认位置，并提示用户
+            let (db, warning) = settings::open_database(&app_dir).expect("Failed to initialize database");
+            if let Some(warning) = warning {
+                app.dialog()
+                    .message(warning)
+                    .title("数据库")
+                    .kind(MessageDialogKind::Warning)
+                    .show(|_| {});
+            }
 
             // Start reminder service
             let reminder_service = ReminderService::new(db.clone(), app.handle().clone());
@@ -31,6 +46,7 @@ pub fn run() {
 
             // Manage database state
             app.manage(db);
+            app.manage(commands::ImportCancel::default());
 
             Ok(())
         })
@@ -41,13 +57,40 @@ pub fn run() {
             commands::update_bookmark,
             commands::delete_bookmark,
             commands::search_bookmarks,
+            commands::find_duplicates,
+            commands::merge_bookmarks,
+            commands::list_tags,
+            commands::rename_tag,
+            commands::merge_tags,
+            commands::delete_tag,
+            commands::list_folders,
+            commands::create_folder,
+            commands::rename_folder,
+            commands::move_folder,
+            commands::delete_folder,
+            commands::list_saved_searches,
+            commands::create_saved_search,
+            commands::update_saved_search,
+            commands::delete_saved_search,
+            commands::evaluate_saved_search,
             commands::get_bookmarks_with_reminders,
             commands::open_url,
             commands::record_visit,
             commands::import_bookmarks,
+            commands::cancel_import,
             commands::export_bookmarks,
+            commands::get_settings,
+            commands::update_settings,
+            reminder::list_reminders,
+            reminder::create_reminder,
+            reminder::update_reminder,
+            reminder::delete_reminder,
             reminder::mark_reminder_completed,
             reminder::snooze_reminder,
+            reminder::dismiss_reminder,
+            reminder::get_reminder_history,
+            reminder::get_reminder_stats,
+            reminder::preview_reminder_schedule,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
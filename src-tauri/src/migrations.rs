@@ -0,0 +1,873 @@
+use anyhow::{bail, Result};
+use crate::canonical_url;
+use rusqlite::{Connection, Transaction};
+
+/// 单个迁移步骤，`version` 为执行后数据库的 `user_version`
+struct Migration {
+    version: u32,
+    description: &'static str,
+    up: fn(&Transaction) -> Result<()>,
+}
+
+/// 按版本顺序排列，只能在末尾追加，不能修改已发布的步骤
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "create bookmarks table",
+        up: create_bookmarks_table,
+    },
+    Migration {
+        version: 2,
+        description: "add visit tracking columns",
+        up: add_visit_columns,
+    },
+    Migration {
+        version: 3,
+        description: "move tags into tags/bookmark_tags tables",
+        up: normalize_tags,
+    },
+    Migration {
+        version: 4,
+        description: "replace category with folder tree",
+        up: create_folders,
+    },
+    Migration {
+        version: 5,
+        description: "add FTS5 search index",
+        up: create_search_index,
+    },
+    Migration {
+        version: 6,
+        description: "add saved searches",
+        up: create_saved_searches,
+    },
+    Migration {
+        version: 7,
+        description: "add canonical_url for duplicate detection",
+        up: add_canonical_url,
+    },
+    Migration {
+        version: 8,
+        description: "add keyword column",
+        up: add_keyword,
+    },
+    Migration {
+        version: 9,
+        description: "add reminder event history",
+        up: create_reminder_events,
+    },
+    Migration {
+        version: 10,
+        description: "move reminders into reminders table",
+        up: create_reminders,
+    },
+    Migration {
+        version: 11,
+        description: "add settings table",
+        up: create_settings,
+    },
+    Migration {
+        version: 12,
+        description: "add bookmarks.exclude_from_digest",
+        up: add_digest_column,
+    },
+    Migration {
+        version: 13,
+        description: "add bookmarks.host",
+        up: add_host,
+    },
+];
+
+/// 当前代码支持的最新 schema 版本
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+pub fn current_version(conn: &Connection) -> Result<u32> {
+    let version: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    Ok(version)
+}
+
+/// 将数据库升级到最新版本，每个步骤在独立事务中执行
+pub fn migrate(conn: &mut Connection) -> Result<()> {
+    let current = current_version(conn)?;
+    let latest = latest_version();
+
+    if current > latest {
+        bail!(
+            "数据库版本 ({}) 高于当前程序支持的版本 ({})，请升级应用后再打开",
+            current,
+            latest
+        );
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = conn.transaction()?;
+        (migration.up)(&tx).map_err(|e| {
+            anyhow::anyhow!(
+                "迁移到版本 {} ({}) 失败: {}",
+                migration.version,
+                migration.description,
+                e
+            )
+        })?;
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
+
+fn column_exists(tx: &Transaction, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = tx.prepare(&format!("PRAGMA table_info({})", table))?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(names.iter().any(|name| name == column))
+}
+
+// v1: 最初的书签表。旧版本没有 user_version，表可能已经存在
+fn create_bookmarks_table(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS bookmarks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            url TEXT NOT NULL,
+            category TEXT,
+            tags TEXT,
+            icon_url TEXT,
+            notes TEXT,
+            reminder TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_bookmarks_category ON bookmarks(category);
+        CREATE INDEX IF NOT EXISTS idx_bookmarks_created_at ON bookmarks(created_at);",
+    )?;
+    Ok(())
+}
+
+// v2: 访问统计字段。未版本化的旧库可能已经通过 ALTER TABLE 添加过
+fn add_visit_columns(tx: &Transaction) -> Result<()> {
+    if !column_exists(tx, "bookmarks", "visit_count")? {
+        tx.execute("ALTER TABLE bookmarks ADD COLUMN visit_count INTEGER DEFAULT 0", [])?;
+    }
+    if !column_exists(tx, "bookmarks", "last_visited")? {
+        tx.execute("ALTER TABLE bookmarks ADD COLUMN last_visited TEXT", [])?;
+    }
+    Ok(())
+}
+
+// v3: 标签从 JSON 字符串拆分到 tags / bookmark_tags 表
+fn normalize_tags(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE
+        );
+        CREATE TABLE bookmark_tags (
+            bookmark_id INTEGER NOT NULL REFERENCES bookmarks(id) ON DELETE CASCADE,
+            tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+            PRIMARY KEY (bookmark_id, tag_id)
+        );
+        CREATE INDEX idx_bookmark_tags_tag_id ON bookmark_tags(tag_id);",
+    )?;
+
+    let mut stmt = tx.prepare("SELECT id, tags FROM bookmarks WHERE tags IS NOT NULL")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(stmt);
+
+    for (bookmark_id, tags_json) in rows {
+        // 旧数据可能不是合法 JSON，按无标签处理
+        let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
+        for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            tx.execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", [tag])?;
+            tx.execute(
+                "INSERT OR IGNORE INTO bookmark_tags (bookmark_id, tag_id)
+                 SELECT ?1, id FROM tags WHERE name = ?2",
+                rusqlite::params![bookmark_id, tag],
+            )?;
+        }
+    }
+
+    tx.execute("ALTER TABLE bookmarks DROP COLUMN tags", [])?;
+    Ok(())
+}
+
+// v4: 分类字符串迁移为 folders 树，原分类成为顶层文件夹。
+// 路径用 / 分隔，分类名中的 / 换成全角的 ／，和导入时一样
+fn create_folders(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE folders (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            parent_id INTEGER REFERENCES folders(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL
+        );
+        CREATE UNIQUE INDEX idx_folders_parent_name ON folders(COALESCE(parent_id, 0), name);
+        CREATE VIEW folder_paths AS
+            WITH RECURSIVE paths(id, path) AS (
+                SELECT id, name FROM folders WHERE parent_id IS NULL
+                UNION ALL
+                SELECT f.id, p.path || '/' || f.name FROM folders f JOIN paths p ON f.parent_id = p.id
+            )
+            SELECT id, path FROM paths;
+        ALTER TABLE bookmarks ADD COLUMN folder_id INTEGER REFERENCES folders(id) ON DELETE SET NULL;
+        CREATE INDEX idx_bookmarks_folder_id ON bookmarks(folder_id);",
+    )?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    tx.execute(
+        "INSERT INTO folders (name, parent_id, created_at)
+         SELECT DISTINCT REPLACE(TRIM(category), '/', '／'), NULL, ?1 FROM bookmarks
+         WHERE category IS NOT NULL AND TRIM(category) != ''",
+        [&now],
+    )?;
+    tx.execute(
+        "UPDATE bookmarks SET folder_id = (
+            SELECT id FROM folders WHERE parent_id IS NULL AND name = REPLACE(TRIM(bookmarks.category), '/', '／')
+         )",
+        [],
+    )?;
+
+    tx.execute_batch(
+        "DROP INDEX IF EXISTS idx_bookmarks_category;
+        ALTER TABLE bookmarks DROP COLUMN category;",
+    )?;
+    Ok(())
+}
+
+// v5: FTS5 全文索引，rowid 与 bookmarks.id 一致，由触发器同步。
+// 触发器中不能使用 CTE，所以文件夹路径通过 folder_paths 视图获取
+fn create_search_index(tx: &Transaction) -> Result<()> {
+    // 重建指定书签的索引行，{ids} 为返回书签 id 的子查询
+    let refresh = |ids: &str| {
+        format!(
+            "DELETE FROM bookmarks_fts WHERE rowid IN ({ids});
+            INSERT INTO bookmarks_fts (rowid, title, url, folder, notes, tags)
+                SELECT b.id, b.title, b.url,
+                    COALESCE((SELECT path FROM folder_paths WHERE id = b.folder_id), ''),
+                    COALESCE(b.notes, ''),
+                    COALESCE((SELECT group_concat(t.name, ' ') FROM bookmark_tags bt
+                        JOIN tags t ON t.id = bt.tag_id WHERE bt.bookmark_id = b.id), '')
+                FROM bookmarks b WHERE b.id IN ({ids});",
+            ids = ids
+        )
+    };
+
+    tx.execute_batch(&format!(
+        "CREATE VIRTUAL TABLE bookmarks_fts USING fts5(
+            title, url, folder, notes, tags,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+
+        CREATE TRIGGER bookmarks_fts_insert AFTER INSERT ON bookmarks BEGIN
+            {bookmark_new}
+        END;
+        CREATE TRIGGER bookmarks_fts_update AFTER UPDATE OF title, url, folder_id, notes ON bookmarks BEGIN
+            {bookmark_new}
+        END;
+        CREATE TRIGGER bookmarks_fts_delete AFTER DELETE ON bookmarks BEGIN
+            DELETE FROM bookmarks_fts WHERE rowid = OLD.id;
+        END;
+
+        CREATE TRIGGER bookmark_tags_fts_insert AFTER INSERT ON bookmark_tags BEGIN
+            {tag_link_new}
+        END;
+        CREATE TRIGGER bookmark_tags_fts_delete AFTER DELETE ON bookmark_tags BEGIN
+            {tag_link_old}
+        END;
+        CREATE TRIGGER tags_fts_update AFTER UPDATE OF name ON tags BEGIN
+            {tag_renamed}
+        END;
+
+        CREATE TRIGGER folders_fts_update AFTER UPDATE OF name, parent_id ON folders BEGIN
+            {folder_changed}
+        END;
+
+        {rebuild}",
+        bookmark_new = refresh("SELECT NEW.id"),
+        tag_link_new = refresh("SELECT NEW.bookmark_id"),
+        tag_link_old = refresh("SELECT OLD.bookmark_id"),
+        tag_renamed = refresh("SELECT bookmark_id FROM bookmark_tags WHERE tag_id = NEW.id"),
+        folder_changed = refresh(
+            "SELECT id FROM bookmarks WHERE folder_id IN (
+                SELECT id FROM folder_paths
+                WHERE path = (SELECT path FROM folder_paths WHERE id = NEW.id)
+                   OR path LIKE (SELECT path FROM folder_paths WHERE id = NEW.id) || '/%'
+            )"
+        ),
+        rebuild = refresh("SELECT id FROM bookmarks"),
+    ))?;
+
+    Ok(())
+}
+
+// v6: 保存的搜索（智能文件夹）
+fn create_saved_searches(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE saved_searches (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            query TEXT NOT NULL DEFAULT '',
+            folder_id INTEGER REFERENCES folders(id) ON DELETE SET NULL,
+            tags TEXT NOT NULL DEFAULT '[]',
+            min_visits INTEGER,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );",
+    )?;
+    Ok(())
+}
+
+// v7: 规范化 URL，用于判重
+fn add_canonical_url(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE bookmarks ADD COLUMN canonical_url TEXT", [])?;
+
+    let mut stmt = tx.prepare("SELECT id, url FROM bookmarks")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(stmt);
+
+    for (id, url) in rows {
+        tx.execute(
+            "UPDATE bookmarks SET canonical_url = ?1 WHERE id = ?2",
+            rusqlite::params![canonical_url::canonicalize(&url), id],
+        )?;
+    }
+
+    tx.execute("CREATE INDEX idx_bookmarks_canonical_url ON bookmarks(canonical_url)", [])?;
+    Ok(())
+}
+
+// v8: 浏览器的书签关键字（地址栏快捷方式）
+fn add_keyword(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE bookmarks ADD COLUMN keyword TEXT", [])?;
+    Ok(())
+}
+
+// v9: 提醒的触发和处理记录，用于历史和连续完成统计
+fn create_reminder_events(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE reminder_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            bookmark_id INTEGER NOT NULL REFERENCES bookmarks(id) ON DELETE CASCADE,
+            kind TEXT NOT NULL,
+            scheduled_for TEXT,
+            created_at TEXT NOT NULL
+        );
+        CREATE INDEX idx_reminder_events_bookmark ON reminder_events(bookmark_id, created_at);",
+    )?;
+    Ok(())
+}
+
+// v10: 提醒从 bookmarks.reminder 的 JSON 移到 reminders 表，一个书签可以有多个提醒。
+// 已有的提醒历史归到迁移出的提醒上
+fn create_reminders(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE reminders (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            bookmark_id INTEGER NOT NULL REFERENCES bookmarks(id) ON DELETE CASCADE,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            frequency TEXT NOT NULL,
+            time TEXT NOT NULL,
+            days TEXT NOT NULL DEFAULT '[]',
+            catch_up TEXT NOT NULL DEFAULT 'fire_once',
+            timezone TEXT,
+            title_template TEXT,
+            message_template TEXT,
+            last_reminded TEXT,
+            next_reminder TEXT,
+            created_at TEXT NOT NULL
+        );
+        CREATE INDEX idx_reminders_bookmark ON reminders(bookmark_id);",
+    )?;
+
+    // 旧数据不是合法 JSON 或缺少必需字段时丢弃
+    let now = chrono::Utc::now().to_rfc3339();
+    tx.execute(
+        "INSERT INTO reminders (bookmark_id, enabled, frequency, time, days, catch_up, timezone,
+            title_template, message_template, last_reminded, next_reminder, created_at)
+         SELECT id,
+            COALESCE(json_extract(reminder, '$.enabled'), 1),
+            json_extract(reminder, '$.frequency'),
+            json_extract(reminder, '$.time'),
+            COALESCE(json_extract(reminder, '$.days'), '[]'),
+            COALESCE(json_extract(reminder, '$.catch_up'), 'fire_once'),
+            json_extract(reminder, '$.timezone'),
+            json_extract(reminder, '$.title_template'),
+            json_extract(reminder, '$.message_template'),
+            json_extract(reminder, '$.last_reminded'),
+            json_extract(reminder, '$.next_reminder'),
+            ?1
+         FROM bookmarks
+         WHERE CASE WHEN json_valid(reminder) THEN
+            json_type(reminder, '$.frequency') = 'object' AND json_type(reminder, '$.time') = 'text'
+         END",
+        [&now],
+    )?;
+
+    tx.execute_batch(
+        "ALTER TABLE reminder_events ADD COLUMN reminder_id INTEGER REFERENCES reminders(id) ON DELETE CASCADE;
+        UPDATE reminder_events SET reminder_id = (
+            SELECT id FROM reminders WHERE reminders.bookmark_id = reminder_events.bookmark_id
+        );
+        CREATE INDEX idx_reminder_events_reminder ON reminder_events(reminder_id, created_at);
+        ALTER TABLE bookmarks DROP COLUMN reminder;",
+    )?;
+    Ok(())
+}
+
+// v11: 应用设置，每项一行，值为 JSON
+fn create_settings(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );",
+    )?;
+    Ok(())
+}
+
+// v12: 不合并到提醒摘要的书签
+fn add_digest_column(tx: &Transaction) -> Result<()> {
+    tx.execute(
+        "ALTER TABLE bookmarks ADD COLUMN exclude_from_digest INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    Ok(())
+}
+
+// v13: 网址的主机名，site: 搜索按它匹配，不会匹配到网址路径或参数中出现的域名
+fn add_host(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE bookmarks ADD COLUMN host TEXT", [])?;
+
+    let mut stmt = tx.prepare("SELECT id, url FROM bookmarks")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(stmt);
+
+    for (id, url) in rows {
+        tx.execute(
+            "UPDATE bookmarks SET host = ?1 WHERE id = ?2",
+            rusqlite::params![canonical_url::host(&url), id],
+        )?;
+    }
+
+    tx.execute("CREATE INDEX idx_bookmarks_host ON bookmarks(host)", [])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::{params, OptionalExtension};
+
+    const NOW: &str = "2026-03-02T00:00:00+00:00";
+    const URL: &str = "https://Doc.Rust-Lang.org/?utm_source=feed";
+    const REMINDER: &str = r#"{"enabled":true,"frequency":{"type":"daily"},"time":"09:00","days":[],"last_reminded":null,"next_reminder":null}"#;
+
+    // 按迁移步骤建出 version 时的数据库，0 为没有 user_version 的旧版数据库
+    fn build(version: u32) -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        if version == 0 {
+            // 旧版程序直接建表，访问统计字段是后来用 ALTER TABLE 加上的
+            conn.execute_batch(
+                "CREATE TABLE bookmarks (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    title TEXT NOT NULL,
+                    url TEXT NOT NULL,
+                    category TEXT,
+                    tags TEXT,
+                    icon_url TEXT,
+                    notes TEXT,
+                    reminder TEXT,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );
+                ALTER TABLE bookmarks ADD COLUMN visit_count INTEGER DEFAULT 0;
+                ALTER TABLE bookmarks ADD COLUMN last_visited TEXT;",
+            )
+            .unwrap();
+            return conn;
+        }
+        for migration in MIGRATIONS.iter().filter(|m| m.version <= version) {
+            let tx = conn.transaction().unwrap();
+            (migration.up)(&tx).unwrap();
+            tx.pragma_update(None, "user_version", migration.version).unwrap();
+            tx.commit().unwrap();
+        }
+        conn
+    }
+
+    // 按 version 时的表结构写入同一个书签：分类（文件夹）“开发”，标签 rust、docs，每天 09:00 的提醒
+    fn seed(conn: &Connection, version: u32) -> i64 {
+        match version {
+            0..=2 => conn.execute(
+                "INSERT INTO bookmarks (title, url, category, tags, notes, reminder, created_at, updated_at)
+                 VALUES ('Rust 文档', ?1, ' 开发 ', '[\"rust\", \"docs\", \" \"]', '官方文档', ?2, ?3, ?3)",
+                params![URL, REMINDER, NOW],
+            ),
+            3 => conn.execute(
+                "INSERT INTO bookmarks (title, url, category, notes, reminder, created_at, updated_at)
+                 VALUES ('Rust 文档', ?1, '开发', '官方文档', ?2, ?3, ?3)",
+                params![URL, REMINDER, NOW],
+            ),
+            _ => {
+                conn.execute(
+                    "INSERT INTO folders (name, parent_id, created_at) VALUES ('开发', NULL, ?1)",
+                    params![NOW],
+                )
+                .unwrap();
+                let folder_id = conn.last_insert_rowid();
+                if version < 10 {
+                    conn.execute(
+                        "INSERT INTO bookmarks (title, url, folder_id, notes, reminder, created_at, updated_at)
+                         VALUES ('Rust 文档', ?1, ?2, '官方文档', ?3, ?4, ?4)",
+                        params![URL, folder_id, REMINDER, NOW],
+                    )
+                } else {
+                    conn.execute(
+                        "INSERT INTO bookmarks (title, url, folder_id, notes, created_at, updated_at)
+                         VALUES ('Rust 文档', ?1, ?2, '官方文档', ?3, ?3)",
+                        params![URL, folder_id, NOW],
+                    )
+                }
+            }
+        }
+        .unwrap();
+        let id = conn.last_insert_rowid();
+
+        if version >= 3 {
+            for tag in ["rust", "docs"] {
+                conn.execute("INSERT INTO tags (name) VALUES (?1)", params![tag]).unwrap();
+                conn.execute(
+                    "INSERT INTO bookmark_tags (bookmark_id, tag_id) SELECT ?1, id FROM tags WHERE name = ?2",
+                    params![id, tag],
+                )
+                .unwrap();
+            }
+        }
+        if version >= 7 {
+            conn.execute(
+                "UPDATE bookmarks SET canonical_url = ?1 WHERE id = ?2",
+                params![canonical_url::canonicalize(URL), id],
+            )
+            .unwrap();
+        }
+        if version >= 13 {
+            conn.execute(
+                "UPDATE bookmarks SET host = ?1 WHERE id = ?2",
+                params![canonical_url::host(URL), id],
+            )
+            .unwrap();
+        }
+        if version >= 10 {
+            conn.execute(
+                "INSERT INTO reminders (bookmark_id, frequency, time, created_at)
+                 VALUES (?1, '{\"type\":\"daily\"}', '09:00', ?2)",
+                params![id, NOW],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO reminder_events (bookmark_id, reminder_id, kind, scheduled_for, created_at)
+                 VALUES (?1, ?2, 'fired', ?3, ?3)",
+                params![id, conn.last_insert_rowid(), NOW],
+            )
+            .unwrap();
+        } else if version >= 9 {
+            conn.execute(
+                "INSERT INTO reminder_events (bookmark_id, kind, scheduled_for, created_at)
+                 VALUES (?1, 'fired', ?2, ?2)",
+                params![id, NOW],
+            )
+            .unwrap();
+        }
+        if version >= 11 {
+            conn.execute(
+                "INSERT INTO settings (key, value, updated_at) VALUES ('settings', '{\"reminder_digest\":true}', ?1)",
+                params![NOW],
+            )
+            .unwrap();
+        }
+        id
+    }
+
+    fn check(conn: &Connection, id: i64, version: u32) {
+        assert_eq!(current_version(conn).unwrap(), latest_version());
+
+        let tags: String = conn
+            .query_row(
+                "SELECT group_concat(name, ',') FROM (
+                    SELECT t.name FROM bookmark_tags bt JOIN tags t ON t.id = bt.tag_id
+                    WHERE bt.bookmark_id = ?1 ORDER BY t.name
+                 )",
+                params![id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(tags, "docs,rust");
+
+        let (folder, canonical, host): (String, String, String) = conn
+            .query_row(
+                "SELECT p.path, b.canonical_url, b.host FROM bookmarks b JOIN folder_paths p ON p.id = b.folder_id
+                 WHERE b.id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(folder, "开发");
+        assert_eq!(canonical, "https://doc.rust-lang.org/");
+        assert_eq!(host, "doc.rust-lang.org");
+
+        for query in ["rust", "docs", "开发", "官方文档"] {
+            let found: Vec<i64> = conn
+                .prepare("SELECT rowid FROM bookmarks_fts WHERE bookmarks_fts MATCH ?1")
+                .unwrap()
+                .query_map(params![query], |row| row.get(0))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(found, [id], "{}", query);
+        }
+
+        let (reminder_id, frequency, time): (i64, String, String) = conn
+            .query_row(
+                "SELECT id, frequency, time FROM reminders WHERE bookmark_id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!((frequency.as_str(), time.as_str()), (r#"{"type":"daily"}"#, "09:00"));
+
+        let events: Vec<Option<i64>> = conn
+            .prepare("SELECT reminder_id FROM reminder_events WHERE bookmark_id = ?1")
+            .unwrap()
+            .query_map(params![id], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let expected = if version >= 9 { vec![Some(reminder_id)] } else { vec![] };
+        assert_eq!(events, expected);
+
+        let digest: Option<bool> = conn
+            .query_row(
+                "SELECT json_extract(value, '$.reminder_digest') FROM settings WHERE key = 'settings'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+            .unwrap();
+        assert_eq!(digest, (version >= 11).then_some(true));
+    }
+
+    #[test]
+    fn migrates_every_historical_schema() {
+        for version in 0..=latest_version() {
+            let mut conn = build(version);
+            let id = seed(&conn, version);
+            migrate(&mut conn).unwrap_or_else(|e| panic!("v{}: {}", version, e));
+            check(&conn, id, version);
+
+            // 已是最新版本时什么也不做
+            migrate(&mut conn).unwrap();
+            check(&conn, id, version);
+        }
+    }
+
+    // 迁移后书签的 (标题, 网址, 文件夹, 标签, 备注, 访问次数, 最后访问, 创建时间)
+    type Row = (String, String, Option<String>, Option<String>, Option<String>, i64, Option<String>, String);
+
+    fn rows(conn: &Connection) -> Vec<Row> {
+        conn.prepare(
+            "SELECT b.title, b.url, p.path,
+                (SELECT group_concat(name, ',') FROM (
+                    SELECT t.name FROM bookmark_tags bt JOIN tags t ON t.id = bt.tag_id
+                    WHERE bt.bookmark_id = b.id ORDER BY t.name
+                )),
+                b.notes, b.visit_count, b.last_visited, b.created_at
+             FROM bookmarks b LEFT JOIN folder_paths p ON p.id = b.folder_id
+             ORDER BY b.id",
+        )
+        .unwrap()
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+                row.get(6)?,
+                row.get(7)?,
+            ))
+        })
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+    }
+
+    #[test]
+    fn migrates_baseline_database() {
+        // 更早的版本还没有访问统计字段，由 v2 补上
+        for visit_columns in [true, false] {
+            let mut conn = Connection::open_in_memory().unwrap();
+            conn.execute_batch(include_str!("../tests/fixtures/baseline_v0.sql")).unwrap();
+            if !visit_columns {
+                conn.execute_batch(
+                    "ALTER TABLE bookmarks DROP COLUMN visit_count;
+                    ALTER TABLE bookmarks DROP COLUMN last_visited;",
+                )
+                .unwrap();
+            }
+            assert_eq!(current_version(&conn).unwrap(), 0);
+            migrate(&mut conn).unwrap();
+            assert_eq!(current_version(&conn).unwrap(), latest_version());
+
+            let (count, last) = if visit_columns {
+                (3, Some("2024-02-01T10:00:00+00:00".to_string()))
+            } else {
+                (0, None)
+            };
+            let rust: Row = (
+                "Rust 文档".into(),
+                "https://Doc.Rust-Lang.org/?utm_source=feed".into(),
+                Some("开发".into()),
+                Some("docs,rust".into()),
+                Some("官方文档".into()),
+                count,
+                last,
+                "2024-01-05T08:00:00+00:00".into(),
+            );
+            let mdn: Row = (
+                "MDN".into(),
+                "https://developer.mozilla.org/zh-CN/".into(),
+                Some("开发".into()),
+                Some("docs".into()),
+                None,
+                0,
+                None,
+                "2024-01-07T08:00:00+00:00".into(),
+            );
+            let news: Row = (
+                "Hacker News".into(),
+                "https://news.ycombinator.com/".into(),
+                None,
+                None,
+                None,
+                0,
+                None,
+                "2024-01-08T08:00:00+00:00".into(),
+            );
+            assert_eq!(rows(&conn), [rust, mdn, news]);
+
+            let derived: Vec<(String, Option<String>, Option<String>)> = conn
+                .prepare("SELECT canonical_url, host, icon_url FROM bookmarks ORDER BY id")
+                .unwrap()
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(
+                derived,
+                [
+                    ("https://doc.rust-lang.org/".into(), Some("doc.rust-lang.org".into()), None),
+                    (
+                        "https://developer.mozilla.org/zh-CN".into(),
+                        Some("developer.mozilla.org".into()),
+                        Some("https://developer.mozilla.org/favicon.ico".into())
+                    ),
+                    ("https://news.ycombinator.com/".into(), Some("news.ycombinator.com".into()), None),
+                ]
+            );
+
+            // 无法解析的提醒被丢弃，其余的保留原来的设置
+            let reminders: Vec<(i64, bool, String, String, String, Option<String>)> = conn
+                .prepare(
+                    "SELECT bookmark_id, enabled, frequency, time, days, next_reminder FROM reminders ORDER BY bookmark_id",
+                )
+                .unwrap()
+                .query_map([], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?))
+                })
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(
+                reminders,
+                [
+                    (
+                        1,
+                        true,
+                        r#"{"type":"daily"}"#.into(),
+                        "09:00".into(),
+                        "[]".into(),
+                        Some("2024-02-02T01:00:00+00:00".into())
+                    ),
+                    (2, false, r#"{"type":"weekly"}"#.into(), "08:30".into(), "[1,5]".into(), None),
+                ]
+            );
+
+            let found: Vec<i64> = conn
+                .prepare("SELECT rowid FROM bookmarks_fts WHERE bookmarks_fts MATCH '开发' ORDER BY rowid")
+                .unwrap()
+                .query_map([], |row| row.get(0))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(found, [1, 2]);
+        }
+    }
+
+    #[test]
+    fn category_with_slash_stays_one_folder() {
+        let mut conn = build(3);
+        for category in ["前端/后端", " 前端/后端 "] {
+            conn.execute(
+                "INSERT INTO bookmarks (title, url, category, created_at, updated_at) VALUES ('t', ?1, ?2, ?3, ?3)",
+                params![URL, category, NOW],
+            )
+            .unwrap();
+        }
+        migrate(&mut conn).unwrap();
+
+        let folders: Vec<(String, Option<i64>)> = conn
+            .prepare("SELECT name, parent_id FROM folders")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(folders, [("前端／后端".to_string(), None)]);
+
+        let paths: Vec<String> = conn
+            .prepare("SELECT p.path FROM bookmarks b JOIN folder_paths p ON p.id = b.folder_id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(paths, ["前端／后端", "前端／后端"]);
+    }
+
+    #[test]
+    fn refuses_newer_database() {
+        let mut conn = build(latest_version());
+        conn.pragma_update(None, "user_version", latest_version() + 1).unwrap();
+        let error = migrate(&mut conn).unwrap_err().to_string();
+        assert!(error.contains("高于当前程序支持的版本"), "{}", error);
+        assert_eq!(current_version(&conn).unwrap(), latest_version() + 1);
+    }
+
+    #[test]
+    fn versions_are_sequential() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version as usize, index + 1, "{}", migration.description);
+        }
+    }
+}
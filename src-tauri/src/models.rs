@@ -5,11 +5,17 @@ pub struct Bookmark {
     pub id: Option<i64>,
     pub title: String,
     pub url: String,
-    pub category: Option<String>,
+    pub folder_id: Option<i64>,
+    pub folder_path: Option<String>,
     pub tags: Vec<String>,
     pub icon_url: Option<String>,
     pub notes: Option<String>,
-    pub reminder: Option<Reminder>,
+    pub keyword: Option<String>,
+    #[serde(default)]
+    pub reminders: Vec<Reminder>,
+    /// 开启提醒摘要时，这个书签的提醒仍然单独通知
+    #[serde(default)]
+    pub exclude_from_digest: bool,
     pub visit_count: i64,
     pub last_visited: Option<String>,
     pub created_at: String,
@@ -18,15 +24,28 @@ pub struct Bookmark {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Reminder {
+    /// 保存后由数据库分配，新建时为空
+    #[serde(default)]
+    pub id: Option<i64>,
     pub enabled: bool,
     pub frequency: ReminderFrequency,
     pub time: String, // HH:MM format
     pub days: Vec<u8>, // 0-6 for Sunday-Saturday
     pub last_reminded: Option<String>,
     pub next_reminder: Option<String>,
+    #[serde(default)]
+    pub catch_up: CatchUpPolicy,
+    /// IANA 时区名，例如 Asia/Shanghai。提醒时间按这个时区计算，保存时未设置则使用系统时区
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// 通知标题和内容模板，可用占位符见 notification.rs，未设置时使用默认文字
+    #[serde(default)]
+    pub title_template: Option<String>,
+    #[serde(default)]
+    pub message_template: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum ReminderFrequency {
     Daily,
@@ -34,16 +53,439 @@ pub enum ReminderFrequency {
     #[serde(rename = "custom")]
     Custom { interval_days: u32 },
     Once,
+    /// RFC 5545 RRULE，例如 FREQ=MONTHLY;BYDAY=1MO，支持的部分见 rrule.rs
+    Rrule { rule: String },
+}
+
+/// 错过提醒时间（电脑休眠、应用未运行）后的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatchUpPolicy {
+    /// 补发一次
+    #[default]
+    FireOnce,
+    /// 不补发，直接安排下一次
+    Skip,
+    /// 每错过一次补发一次
+    FireAll,
+}
+
+impl CatchUpPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CatchUpPolicy::FireOnce => "fire_once",
+            CatchUpPolicy::Skip => "skip",
+            CatchUpPolicy::FireAll => "fire_all",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "fire_once" => Some(CatchUpPolicy::FireOnce),
+            "skip" => Some(CatchUpPolicy::Skip),
+            "fire_all" => Some(CatchUpPolicy::FireAll),
+            _ => None,
+        }
+    }
+}
+
+/// 免打扰设置，对所有提醒生效，保存在 Settings 中
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuietHours {
+    /// 每天的免打扰时段，按系统时区
+    #[serde(default)]
+    pub windows: Vec<QuietWindow>,
+    #[serde(default)]
+    pub policy: QuietHoursPolicy,
+    /// 暂停所有提醒直到这个时间（RFC 3339），None 为不暂停
+    #[serde(default)]
+    pub paused_until: Option<String>,
+}
+
+/// 免打扰时段 HH:MM，结束时间早于开始时间时跨过午夜，例如 22:00–07:00
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuietWindow {
+    pub start: String,
+    pub end: String,
+}
+
+/// 免打扰期间到期的提醒的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuietHoursPolicy {
+    /// 推迟到免打扰结束时提醒
+    #[default]
+    Defer,
+    /// 不提醒，直接安排下一次
+    Drop,
+}
+
+/// 应用设置，保存在 settings 表中，缺少的字段使用默认值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// 提醒服务最长休眠的分钟数。系统睡眠期间计时器不走，唤醒后最迟这么久会补发错过的提醒
+    pub poll_interval_minutes: u32,
+    /// 通知文字的语言，None 时跟随系统
+    pub notification_language: Option<NotificationLanguage>,
+    /// 提醒没有设置模板时使用的通知标题和内容，None 时使用对应语言的默认文字
+    pub default_title_template: Option<String>,
+    pub default_message_template: Option<String>,
+    /// 免打扰时段和暂停
+    pub quiet_hours: QuietHours,
+    /// 是否把同时到期的多个提醒合并成一条摘要通知
+    pub reminder_digest: bool,
+    /// 数据库文件的路径，None 为应用数据目录下的 bookmarks.db，重启后生效。
+    /// 保存在应用数据目录的单独文件中，不在数据库里
+    pub database_path: Option<String>,
+    /// 导出时不指定格式和列时使用的默认值
+    pub export_format: ExportFormat,
+    pub export_columns: Vec<CsvColumn>,
+    pub export_include_saved_searches: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            poll_interval_minutes: 5,
+            notification_language: None,
+            default_title_template: None,
+            default_message_template: None,
+            quiet_hours: QuietHours::default(),
+            reminder_digest: false,
+            database_path: None,
+            export_format: ExportFormat::default(),
+            export_columns: CsvColumn::ALL.to_vec(),
+            export_include_saved_searches: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationLanguage {
+    Chinese,
+    English,
+}
+
+/// 提醒到期时的通知内容，同时作为 reminder-triggered 事件发送给前端
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReminderNotification {
+    pub bookmark: Bookmark,
+    /// 到期的那个提醒
+    pub reminder: Reminder,
+    pub title: String,
+    pub body: String,
+    /// 系统通知带有操作按钮，由后台处理，前端不需要再询问
+    pub actions: bool,
+}
+
+/// 开启提醒摘要时，同时到期的多个提醒合并成一条通知，作为 reminder-digest 事件发送给前端
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReminderDigest {
+    pub title: String,
+    /// 每个提醒的通知内容一行
+    pub body: String,
+    pub items: Vec<ReminderNotification>,
+    /// 系统通知带有操作按钮，按钮对摘要中的所有提醒生效
+    pub actions: bool,
+}
+
+/// 提醒历史中的事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderEventKind {
+    Fired,
+    Completed,
+    Snoozed,
+    Dismissed,
+    /// 免打扰期间到期、按设置不提醒的
+    Skipped,
+}
+
+impl ReminderEventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReminderEventKind::Fired => "fired",
+            ReminderEventKind::Completed => "completed",
+            ReminderEventKind::Snoozed => "snoozed",
+            ReminderEventKind::Dismissed => "dismissed",
+            ReminderEventKind::Skipped => "skipped",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "fired" => Some(ReminderEventKind::Fired),
+            "completed" => Some(ReminderEventKind::Completed),
+            "snoozed" => Some(ReminderEventKind::Snoozed),
+            "dismissed" => Some(ReminderEventKind::Dismissed),
+            "skipped" => Some(ReminderEventKind::Skipped),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReminderEvent {
+    pub id: i64,
+    pub bookmark_id: i64,
+    /// 提醒移到 reminders 表之前的记录可能没有
+    pub reminder_id: Option<i64>,
+    pub bookmark_title: String,
+    pub kind: ReminderEventKind,
+    /// fired 为原定的提醒时间，snoozed 为推迟到的时间
+    pub scheduled_for: Option<String>,
+    pub created_at: String,
+}
+
+/// 单个提醒的统计。每次触发算一期，下次触发前完成即算该期完成
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReminderStats {
+    pub reminder_id: i64,
+    pub bookmark_id: i64,
+    pub fired: usize,
+    pub completed: usize,
+    pub snoozed: usize,
+    pub dismissed: usize,
+    pub skipped: usize,
+    /// 截至最近一期连续完成的期数，最近一期尚未完成时不算中断
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    /// 已结束的各期中完成的比例，没有数据时为 0
+    pub completion_rate: f64,
+    pub last_completed: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub bookmark: Bookmark,
+    pub snippet: Option<String>, // 命中内容片段，关键词用 <mark> 标记
+    pub score: f64,              // BM25 分数，越小越相关
+}
+
+/// 保存的搜索（智能文件夹），query 使用搜索语法，其余字段为附加过滤条件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub id: i64,
+    pub name: String,
+    pub query: String,
+    pub folder_id: Option<i64>,
+    pub tags: Vec<String>,
+    pub min_visits: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedSearchInput {
+    pub name: String,
+    #[serde(default)]
+    pub query: String,
+    pub folder_id: Option<i64>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub min_visits: Option<i64>,
+}
+
+/// 规范化 URL 相同的一组书签，按创建时间排序
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub canonical_url: String,
+    pub bookmarks: Vec<Bookmark>,
+}
+
+/// 导入时遇到已存在（规范化 URL 相同）书签的处理方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportPolicy {
+    #[default]
+    SkipExisting,
+    UpdateExisting,
+    AlwaysCreate,
+}
+
+/// 导入文件的格式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    /// Netscape 书签 HTML（各浏览器的导出文件）
+    #[default]
+    Html,
+    /// Firefox 配置目录中的 places.sqlite
+    FirefoxPlaces,
+    /// Chromium 系浏览器的 Bookmarks JSON 文件，也可以是配置目录
+    Chromium,
+    /// 本应用导出的 JSON 备份
+    Json,
+    /// 带表头的 CSV，列名见 CsvColumn
+    Csv,
+    /// Pinboard 的 JSON 导出
+    Pinboard,
+    /// Pocket 的 HTML 或 CSV 导出
+    Pocket,
+    /// Raindrop.io 的 CSV 导出
+    Raindrop,
+}
+
+/// 导出文件的格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Html,
+    Json,
+    Csv,
+}
+
+/// JSON 备份文件的结构。字段有不兼容的变化时增加 version。
+///
+/// 版本 1 中每个书签最多一个提醒（reminder 字段），版本 2 改为 reminders 数组
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookmarkBackup {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl BookmarkBackup {
+    pub const FORMAT: &'static str = "bookmark-manager";
+    pub const VERSION: u32 = 2;
+}
+
+/// CSV 导入导出的列，表头使用 name() 返回的名称
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvColumn {
+    Title,
+    Url,
+    Folder,
+    Tags,
+    Notes,
+    Keyword,
+    IconUrl,
+    VisitCount,
+    LastVisited,
+    CreatedAt,
+    UpdatedAt,
+    Reminder,
+}
+
+impl CsvColumn {
+    pub const ALL: [CsvColumn; 12] = [
+        CsvColumn::Title,
+        CsvColumn::Url,
+        CsvColumn::Folder,
+        CsvColumn::Tags,
+        CsvColumn::Notes,
+        CsvColumn::Keyword,
+        CsvColumn::IconUrl,
+        CsvColumn::VisitCount,
+        CsvColumn::LastVisited,
+        CsvColumn::CreatedAt,
+        CsvColumn::UpdatedAt,
+        CsvColumn::Reminder,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CsvColumn::Title => "title",
+            CsvColumn::Url => "url",
+            CsvColumn::Folder => "folder",
+            CsvColumn::Tags => "tags",
+            CsvColumn::Notes => "notes",
+            CsvColumn::Keyword => "keyword",
+            CsvColumn::IconUrl => "icon_url",
+            CsvColumn::VisitCount => "visit_count",
+            CsvColumn::LastVisited => "last_visited",
+            CsvColumn::CreatedAt => "created_at",
+            CsvColumn::UpdatedAt => "updated_at",
+            CsvColumn::Reminder => "reminder",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::ALL.into_iter().find(|c| c.name().eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub failed: Vec<ImportFailure>,
+    pub dry_run: bool,
+    /// 导入被取消，已提交的批次会保留
+    #[serde(default)]
+    pub cancelled: bool,
+    /// 导入中途出错的原因，出错前已提交的批次会保留
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// 导入过程中通过 import-progress 事件发送的进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportProgress {
+    pub processed: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    /// 书签总数，边读边解析时未知
+    pub total: Option<usize>,
+    pub bytes_read: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportFailure {
+    pub title: String,
+    pub url: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub bookmark_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Folder {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub path: String, // 以 / 分隔的完整路径
+    pub bookmark_count: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeleteFolderMode {
+    Cascade,
+    Reparent,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateBookmarkInput {
     pub title: String,
     pub url: String,
-    pub category: Option<String>,
+    pub folder_id: Option<i64>,
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub icon_url: Option<String>,
     pub notes: Option<String>,
-    pub reminder: Option<Reminder>,
+    #[serde(default)]
+    pub keyword: Option<String>,
+    #[serde(default)]
+    pub reminders: Vec<Reminder>,
+    #[serde(default)]
+    pub exclude_from_digest: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -51,8 +493,12 @@ pub struct UpdateBookmarkInput {
     pub id: i64,
     pub title: Option<String>,
     pub url: Option<String>,
-    pub category: Option<String>,
+    pub folder_id: Option<i64>,
     pub tags: Option<Vec<String>>,
     pub notes: Option<String>,
-    pub reminder: Option<Reminder>,
+    /// 为 None 时不修改提醒；否则替换为给定的列表，带 id 的提醒沿用原有记录
+    #[serde(default)]
+    pub reminders: Option<Vec<Reminder>>,
+    #[serde(default)]
+    pub exclude_from_digest: Option<bool>,
 }
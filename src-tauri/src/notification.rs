@@ -0,0 +1,467 @@
+use crate::models::{Bookmark, NotificationLanguage, Reminder, ReminderDigest, ReminderNotification, Settings};
+use anyhow::{bail, Result};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// 通知上“稍后提醒”按钮推迟的分钟数
+pub const SNOOZE_MINUTES: i64 = 10;
+
+/// 消息模板中可用的占位符
+const PLACEHOLDERS: [&str; 5] = ["title", "url", "folder", "streak", "time"];
+
+/// 通知文字使用的语言，跟随系统语言
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    Chinese,
+    English,
+}
+
+impl Language {
+    pub fn system() -> Self {
+        match sys_locale::get_locale() {
+            Some(locale) if !locale.to_lowercase().starts_with("zh") => Language::English,
+            _ => Language::Chinese,
+        }
+    }
+
+    /// 设置中选择的语言，没有选择时使用 system
+    pub fn from_setting(setting: Option<NotificationLanguage>, system: Language) -> Self {
+        match setting {
+            Some(NotificationLanguage::Chinese) => Language::Chinese,
+            Some(NotificationLanguage::English) => Language::English,
+            None => system,
+        }
+    }
+
+    fn default_title(self) -> &'static str {
+        match self {
+            Language::Chinese => "签到提醒",
+            Language::English => "Reminder",
+        }
+    }
+
+    fn default_body(self) -> &'static str {
+        match self {
+            Language::Chinese => "该去 {title} 签到了！",
+            Language::English => "Time to check in at {title}!",
+        }
+    }
+
+    fn digest_title(self, count: usize) -> String {
+        match self {
+            Language::Chinese => format!("签到提醒（{} 个）", count),
+            Language::English => format!("{} reminders", count),
+        }
+    }
+
+    // macOS 通知上展开操作菜单的按钮
+    #[cfg(target_os = "macos")]
+    fn actions_label(self) -> &'static str {
+        match self {
+            Language::Chinese => "处理",
+            Language::English => "Actions",
+        }
+    }
+
+    fn action_label(self, action: Action) -> &'static str {
+        match (self, action) {
+            (Language::Chinese, Action::Open) => "打开",
+            (Language::Chinese, Action::Done) => "完成",
+            (Language::Chinese, Action::Snooze) => "10 分钟后提醒",
+            (Language::English, Action::Open) => "Open",
+            (Language::English, Action::Done) => "Done",
+            (Language::English, Action::Snooze) => "Snooze 10m",
+        }
+    }
+}
+
+/// 通知上的操作按钮
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Open,
+    Done,
+    Snooze,
+}
+
+impl Action {
+    pub const ALL: [Action; 3] = [Action::Open, Action::Done, Action::Snooze];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Action::Open => "open",
+            Action::Done => "done",
+            Action::Snooze => "snooze",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.id() == id)
+    }
+}
+
+/// 检查模板中的占位符，拼错的占位符在保存时报错
+pub fn validate_template(template: &str) -> Result<()> {
+    for name in placeholders(template) {
+        if !PLACEHOLDERS.contains(&name) {
+            bail!(
+                "未知的占位符 {{{}}}，可用的有：{}",
+                name,
+                PLACEHOLDERS.map(|p| format!("{{{}}}", p)).join("、")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// 按提醒的模板生成通知内容，没有模板时依次使用设置中的默认模板和对应语言的默认文字
+pub fn compose(
+    bookmark: &Bookmark,
+    reminder: &Reminder,
+    streak: usize,
+    language: Language,
+    settings: &Settings,
+) -> ReminderNotification {
+    let template = |templates: [Option<&String>; 2], default: &'static str| {
+        templates
+            .into_iter()
+            .flatten()
+            .map(|t| t.trim())
+            .find(|t| !t.is_empty())
+            .unwrap_or(default)
+            .to_string()
+    };
+    let title = template(
+        [reminder.title_template.as_ref(), settings.default_title_template.as_ref()],
+        language.default_title(),
+    );
+    let body = template(
+        [reminder.message_template.as_ref(), settings.default_message_template.as_ref()],
+        language.default_body(),
+    );
+
+    ReminderNotification {
+        bookmark: bookmark.clone(),
+        reminder: reminder.clone(),
+        title: render(&title, bookmark, reminder, streak),
+        body: render(&body, bookmark, reminder, streak),
+        actions: false,
+    }
+}
+
+/// 把同时到期的多个提醒合并成一条摘要，内容是每个提醒的通知内容，一个一行
+pub fn compose_digest(items: Vec<ReminderNotification>, language: Language) -> ReminderDigest {
+    ReminderDigest {
+        title: language.digest_title(items.len()),
+        body: items.iter().map(|item| format!("• {}", item.body)).collect::<Vec<_>>().join("\n"),
+        items,
+        actions: false,
+    }
+}
+
+/// 替换模板中的占位符，不认识的原样保留
+pub fn render(template: &str, bookmark: &Bookmark, reminder: &Reminder, streak: usize) -> String {
+    let mut output = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            let value = match &after[..end] {
+                "title" => bookmark.title.clone(),
+                "url" => bookmark.url.clone(),
+                "folder" => bookmark.folder_path.clone().unwrap_or_default(),
+                "streak" => streak.to_string(),
+                "time" => reminder.time.clone(),
+                _ => return None,
+            };
+            Some((value, end))
+        });
+
+        match value {
+            Some((value, end)) => {
+                output.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                output.push('{');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+// 模板中形如 {name} 的占位符名称
+fn placeholders(template: &str) -> impl Iterator<Item = &str> {
+    template.split('{').skip(1).filter_map(|part| {
+        let name = &part[..part.find('}')?];
+        let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        valid.then_some(name)
+    })
+}
+
+/// 显示系统通知，带“打开”“完成”“稍后提醒”按钮，点击后在后台处理，不需要打开主窗口。
+/// 按钮不可用时显示普通通知，由前端询问。返回通知是否带按钮
+pub fn show(app_handle: &AppHandle, notification: &ReminderNotification, language: Language) -> bool {
+    show_items(
+        app_handle,
+        &notification.title,
+        &notification.body,
+        std::slice::from_ref(notification),
+        language,
+    )
+}
+
+/// 显示摘要通知。“完成”和“稍后提醒”对其中所有提醒生效，“打开”显示主窗口中的今日提醒
+pub fn show_digest(app_handle: &AppHandle, digest: &ReminderDigest, language: Language) -> bool {
+    show_items(app_handle, &digest.title, &digest.body, &digest.items, language)
+}
+
+fn show_items(
+    app_handle: &AppHandle,
+    title: &str,
+    body: &str,
+    items: &[ReminderNotification],
+    language: Language,
+) -> bool {
+    if actions::show(app_handle, title, body, items, language).is_ok() {
+        return true;
+    }
+
+    let _ = app_handle.notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show();
+    false
+}
+
+mod actions {
+    use super::{Action, Language, SNOOZE_MINUTES};
+    use crate::database::Database;
+    use crate::models::ReminderNotification;
+    use crate::reminder::{self, Clock, SystemClock};
+    use anyhow::{anyhow, Result};
+    use tauri::{AppHandle, Emitter, Manager};
+
+    // 通知上按钮作用的一个提醒
+    struct Target {
+        bookmark_id: i64,
+        reminder_id: i64,
+        url: String,
+    }
+
+    pub fn show(
+        app_handle: &AppHandle,
+        title: &str,
+        body: &str,
+        items: &[ReminderNotification],
+        language: Language,
+    ) -> Result<()> {
+        let targets = items
+            .iter()
+            .map(|item| {
+                Ok(Target {
+                    bookmark_id: item.bookmark.id.ok_or_else(|| anyhow!("书签没有 id"))?,
+                    reminder_id: item.reminder.id.ok_or_else(|| anyhow!("提醒没有 id"))?,
+                    url: item.bookmark.url.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        platform::show(app_handle, title, body, language, targets)
+    }
+
+    // 用户点了通知上的按钮
+    fn handle(app_handle: &AppHandle, targets: &[Target], action: Action) {
+        // 摘要不一次打开所有网址，而是在主窗口中列出这些提醒
+        if action == Action::Open && targets.len() > 1 {
+            show_reminders(app_handle);
+            return;
+        }
+        for target in targets {
+            if perform(app_handle, target, action).is_ok() {
+                let _ = app_handle.emit("reminder-action-performed", target.reminder_id);
+            }
+        }
+    }
+
+    fn perform(app_handle: &AppHandle, target: &Target, action: Action) -> Result<()> {
+        let db = app_handle.state::<Database>();
+        let now = SystemClock.now();
+        match action {
+            Action::Open => {
+                opener::open(&target.url)?;
+                db.record_visit(target.bookmark_id)
+            }
+            Action::Done => reminder::complete(&db, target.reminder_id, now),
+            Action::Snooze => reminder::snooze(&db, target.reminder_id, SNOOZE_MINUTES, now),
+        }
+    }
+
+    fn show_reminders(app_handle: &AppHandle) {
+        if let Some(window) = app_handle.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.unminimize();
+            let _ = window.set_focus();
+        }
+        let _ = app_handle.emit("show-reminders", ());
+    }
+
+    // Linux 和 macOS 上等待点击会阻塞，每条带按钮的通知占用一个线程直到通知被点击或关闭
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    mod slot {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // 同时等待点击的通知数上限。有的通知服务会把通知一直留在通知中心里，
+        // 超过上限时新的通知不带按钮
+        const MAX_WAITING: usize = 8;
+        static WAITING: AtomicUsize = AtomicUsize::new(0);
+
+        // 占用一个等待名额，线程结束时归还
+        pub struct Slot;
+
+        impl Slot {
+            pub fn acquire() -> Option<Slot> {
+                WAITING
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < MAX_WAITING).then_some(n + 1))
+                    .ok()
+                    .map(|_| Slot)
+            }
+        }
+
+        impl Drop for Slot {
+            fn drop(&mut self) {
+                WAITING.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    mod platform {
+        use super::slot::Slot;
+        use super::{handle, Target};
+        use crate::notification::{Action, Language};
+        use anyhow::{anyhow, Result};
+        use tauri::AppHandle;
+
+        pub fn show(app_handle: &AppHandle, title: &str, body: &str, language: Language, targets: Vec<Target>) -> Result<()> {
+            let slot = Slot::acquire().ok_or_else(|| anyhow!("等待处理的通知太多"))?;
+
+            let mut builder = notify_rust::Notification::new();
+            builder
+                .appname(&app_handle.package_info().name)
+                .summary(title)
+                .body(body);
+            for action in Action::ALL {
+                builder.action(action.id(), language.action_label(action));
+            }
+            let notification = builder.show()?;
+
+            let app_handle = app_handle.clone();
+            std::thread::spawn(move || {
+                let _slot = slot;
+                notification.wait_for_action(|action_id| {
+                    if let Some(action) = Action::from_id(action_id) {
+                        handle(&app_handle, &targets, action);
+                    }
+                });
+            });
+            Ok(())
+        }
+    }
+
+    // macOS 的通知只有一个主按钮，其余操作放在它的下拉菜单中，点击后返回按钮文字
+    #[cfg(target_os = "macos")]
+    mod platform {
+        use super::slot::Slot;
+        use super::{handle, Target};
+        use crate::notification::{Action, Language};
+        use anyhow::{anyhow, Result};
+        use mac_notification_sys::{MainButton, NotificationResponse};
+        use tauri::AppHandle;
+        use tauri_plugin_notification::NotificationExt;
+
+        pub fn show(app_handle: &AppHandle, title: &str, body: &str, language: Language, targets: Vec<Target>) -> Result<()> {
+            let slot = Slot::acquire().ok_or_else(|| anyhow!("等待处理的通知太多"))?;
+            // 开发时应用没有安装，借用终端的身份发送通知，与通知插件一致
+            let bundle = if tauri::is_dev() { "com.apple.Terminal" } else { &app_handle.config().identifier };
+            // 只能设置一次，之后的调用返回 AlreadySet
+            let _ = mac_notification_sys::set_application(bundle);
+
+            let app_handle = app_handle.clone();
+            let (title, body) = (title.to_string(), body.to_string());
+            std::thread::spawn(move || {
+                let _slot = slot;
+                let labels = Action::ALL.map(|action| language.action_label(action));
+                let response = mac_notification_sys::Notification::new()
+                    .title(&title)
+                    .message(&body)
+                    .main_button(MainButton::DropdownActions(language.actions_label(), &labels))
+                    .send();
+                match response {
+                    Ok(NotificationResponse::ActionButton(label)) => {
+                        if let Some(action) = Action::ALL.into_iter().find(|a| language.action_label(*a) == label) {
+                            handle(&app_handle, &targets, action);
+                        }
+                    }
+                    Ok(_) => {}
+                    // 发送失败时仍然显示一条普通通知
+                    Err(_) => {
+                        let _ = app_handle.notification().builder().title(&title).body(&body).show();
+                    }
+                }
+            });
+            Ok(())
+        }
+    }
+
+    // Windows 的通知点击后由系统回调，不需要等待线程
+    #[cfg(windows)]
+    mod platform {
+        use super::{handle, Target};
+        use crate::notification::{Action, Language};
+        use anyhow::Result;
+        use tauri::AppHandle;
+        use tauri_winrt_notification::Toast;
+
+        pub fn show(app_handle: &AppHandle, title: &str, body: &str, language: Language, targets: Vec<Target>) -> Result<()> {
+            let mut toast = Toast::new(&app_id(app_handle)?).title(title).text1(body);
+            for action in Action::ALL {
+                toast = toast.add_button(language.action_label(action), action.id());
+            }
+            let app_handle = app_handle.clone();
+            toast
+                .on_activated(move |action_id| {
+                    if let Some(action) = action_id.as_deref().and_then(Action::from_id) {
+                        handle(&app_handle, &targets, action);
+                    }
+                    Ok(())
+                })
+                .show()?;
+            Ok(())
+        }
+
+        // 只有安装后的应用注册了 AppUserModelID，从 target 目录运行时借用 PowerShell 的，与通知插件一致
+        fn app_id(app_handle: &AppHandle) -> Result<String> {
+            let exe = tauri::utils::platform::current_exe()?;
+            let dev = exe
+                .parent()
+                .is_some_and(|dir| dir.ends_with("target/debug") || dir.ends_with("target/release"));
+            Ok(if dev { Toast::POWERSHELL_APP_ID.to_string() } else { app_handle.config().identifier.clone() })
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    mod platform {
+        use super::Target;
+        use crate::notification::Language;
+        use anyhow::{bail, Result};
+        use tauri::AppHandle;
+
+        pub fn show(_: &AppHandle, _: &str, _: &str, _: Language, _: Vec<Target>) -> Result<()> {
+            bail!("这个系统的通知不支持按钮")
+        }
+    }
+}
@@ -0,0 +1,528 @@
+use chrono::NaiveDate;
+use rusqlite::types::Value;
+use std::fmt;
+
+/// 搜索语法解析错误，position 为出错位置（字符下标）
+#[derive(Debug, Clone)]
+pub struct QueryError {
+    pub message: String,
+    pub position: usize,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "搜索语法错误（第 {} 个字符）：{}", self.position + 1, self.message)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+fn error<T>(position: usize, message: impl Into<String>) -> Result<T, QueryError> {
+    Err(QueryError {
+        message: message.into(),
+        position,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    fn as_sql(self) -> &'static str {
+        match self {
+            Comparison::Eq => "=",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+        }
+    }
+
+    // 拆出 ">=5" 这类值的比较符
+    fn split(value: &str) -> (Comparison, &str) {
+        for (prefix, cmp) in [
+            (">=", Comparison::Ge),
+            ("<=", Comparison::Le),
+            (">", Comparison::Gt),
+            ("<", Comparison::Lt),
+            ("=", Comparison::Eq),
+        ] {
+            if let Some(rest) = value.strip_prefix(prefix) {
+                return (cmp, rest);
+            }
+        }
+        (Comparison::Eq, value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    /// 普通关键词，子串匹配
+    Text(String),
+    /// 引号括起的短语，整体匹配
+    Phrase(String),
+    Tag(String),
+    /// 文件夹路径，包含其子文件夹
+    Folder(String),
+    Site(String),
+    Visited(Comparison, i64),
+    Created(Comparison, NaiveDate),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Term(Term),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    Or,
+    Not,
+    Word(String),
+    Phrase(String),
+    Field(String, String),
+}
+
+const FIELDS: &[&str] = &["tag", "folder", "site", "visited", "created"];
+
+// 括号和 - 最多嵌套的层数，解析是递归的，限制层数避免栈溢出
+const MAX_DEPTH: usize = 32;
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, QueryError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    // 读取引号内的内容，i 指向开头的引号
+    let read_quoted = |i: &mut usize| -> Result<String, QueryError> {
+        let start = *i;
+        *i += 1;
+        let mut value = String::new();
+        while *i < chars.len() && chars[*i] != '"' {
+            value.push(chars[*i]);
+            *i += 1;
+        }
+        if *i >= chars.len() {
+            return error(start, "引号未闭合");
+        }
+        *i += 1;
+        Ok(value)
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' {
+            tokens.push((start, Token::LParen));
+            i += 1;
+        } else if c == ')' {
+            tokens.push((start, Token::RParen));
+            i += 1;
+        } else if c == '-' && chars.get(i + 1).is_some_and(|n| !n.is_whitespace()) {
+            tokens.push((start, Token::Not));
+            i += 1;
+        } else if c == '"' {
+            let phrase = read_quoted(&mut i)?;
+            tokens.push((start, Token::Phrase(phrase)));
+        } else {
+            let mut word = String::new();
+            while i < chars.len() && !chars[i].is_whitespace() && chars[i] != '(' && chars[i] != ')' {
+                if chars[i] == '"' && word.ends_with(':') {
+                    break;
+                }
+                word.push(chars[i]);
+                i += 1;
+            }
+
+            let field = word
+                .split_once(':')
+                .filter(|(name, _)| FIELDS.contains(&name.to_lowercase().as_str()))
+                .map(|(name, value)| (name.to_lowercase(), value.to_string()));
+
+            match field {
+                Some((name, value)) => {
+                    let value = if value.is_empty() && chars.get(i) == Some(&'"') {
+                        read_quoted(&mut i)?
+                    } else {
+                        value
+                    };
+                    if value.trim().is_empty() {
+                        return error(start, format!("{}: 缺少值", name));
+                    }
+                    tokens.push((start, Token::Field(name, value)));
+                }
+                None if word == "OR" => tokens.push((start, Token::Or)),
+                // 未知前缀（例如 https://）按普通关键词处理
+                None => tokens.push((start, Token::Word(word))),
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    end: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.pos).map(|(p, _)| *p).unwrap_or(self.end)
+    }
+
+    // 进入一层括号或 -，超过 MAX_DEPTH 层时报错
+    fn nested(
+        &mut self,
+        position: usize,
+        parse: impl FnOnce(&mut Self) -> Result<Expr, QueryError>,
+    ) -> Result<Expr, QueryError> {
+        if self.depth >= MAX_DEPTH {
+            return error(position, format!("括号和 - 最多嵌套 {} 层", MAX_DEPTH));
+        }
+        self.depth += 1;
+        let expr = parse(self);
+        self.depth -= 1;
+        expr
+    }
+
+    // or_expr := and_expr ("OR" and_expr)*
+    fn parse_or(&mut self) -> Result<Expr, QueryError> {
+        let mut items = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            items.push(self.parse_and()?);
+        }
+        Ok(if items.len() == 1 { items.remove(0) } else { Expr::Or(items) })
+    }
+
+    // and_expr := unary+
+    fn parse_and(&mut self) -> Result<Expr, QueryError> {
+        let mut items = Vec::new();
+        while let Some(token) = self.peek() {
+            if matches!(token, Token::Or | Token::RParen) {
+                break;
+            }
+            items.push(self.parse_unary()?);
+        }
+
+        match items.len() {
+            0 => match self.peek() {
+                Some(Token::Or) => error(self.position(), "OR 前后都需要搜索条件"),
+                Some(Token::RParen) => error(self.position(), "多余的右括号"),
+                _ => error(self.position(), "缺少搜索条件"),
+            },
+            1 => Ok(items.remove(0)),
+            _ => Ok(Expr::And(items)),
+        }
+    }
+
+    // unary := "-" unary | "(" or_expr ")" | term
+    fn parse_unary(&mut self) -> Result<Expr, QueryError> {
+        let position = self.position();
+        let token = match self.tokens.get(self.pos) {
+            Some((_, token)) => token.clone(),
+            None => return error(position, "缺少搜索条件"),
+        };
+        self.pos += 1;
+
+        match token {
+            Token::Not => self.nested(position, |p| Ok(Expr::Not(Box::new(p.parse_unary()?)))),
+            Token::LParen => self.nested(position, |p| {
+                let expr = p.parse_or()?;
+                if p.peek() != Some(&Token::RParen) {
+                    return error(position, "括号未闭合");
+                }
+                p.pos += 1;
+                Ok(expr)
+            }),
+            Token::Word(word) => Ok(Expr::Term(Term::Text(word))),
+            Token::Phrase(phrase) => Ok(Expr::Term(Term::Phrase(phrase))),
+            Token::Field(name, value) => Ok(Expr::Term(parse_field(position, &name, &value)?)),
+            Token::Or | Token::RParen => error(position, "缺少搜索条件"),
+        }
+    }
+}
+
+fn parse_field(position: usize, name: &str, value: &str) -> Result<Term, QueryError> {
+    let value = value.trim();
+    match name {
+        "tag" => Ok(Term::Tag(value.to_string())),
+        "folder" => Ok(Term::Folder(value.trim_matches('/').to_string())),
+        "site" => Ok(Term::Site(value.to_lowercase())),
+        "visited" => {
+            let (cmp, number) = Comparison::split(value);
+            match number.parse::<i64>() {
+                Ok(n) => Ok(Term::Visited(cmp, n)),
+                Err(_) => error(position, format!("visited: 需要数字，例如 visited:>5，实际为 \"{}\"", value)),
+            }
+        }
+        "created" => {
+            let (cmp, date) = Comparison::split(value);
+            match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                Ok(d) => Ok(Term::Created(cmp, d)),
+                Err(_) => error(position, format!("created: 需要 YYYY-MM-DD 格式的日期，实际为 \"{}\"", value)),
+            }
+        }
+        _ => error(position, format!("未知字段: {}", name)),
+    }
+}
+
+/// 解析搜索语句，空语句返回 None
+pub fn parse(input: &str) -> Result<Option<Expr>, QueryError> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Ok(None);
+    }
+
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        end: input.chars().count(),
+        depth: 0,
+    };
+    let expr = parser.parse_or()?;
+    if parser.pos < parser.tokens.len() {
+        return error(parser.position(), "多余的右括号");
+    }
+    Ok(Some(expr))
+}
+
+impl Expr {
+    /// 只包含普通关键词时可以直接走全文索引排序
+    pub fn plain_words(&self) -> Option<Vec<&str>> {
+        match self {
+            Expr::Term(Term::Text(word)) => Some(vec![word.as_str()]),
+            Expr::And(items) => items.iter().try_fold(Vec::new(), |mut words, item| {
+                match item {
+                    Expr::Term(Term::Text(word)) => words.push(word.as_str()),
+                    _ => return None,
+                }
+                Some(words)
+            }),
+            _ => None,
+        }
+    }
+
+    /// 编译为针对 bookmarks 表的 WHERE 条件，参数按顺序追加到 params
+    pub fn to_sql(&self, params: &mut Vec<Value>) -> String {
+        match self {
+            Expr::And(items) => Self::join(items, " AND ", params),
+            Expr::Or(items) => Self::join(items, " OR ", params),
+            Expr::Not(inner) => format!("NOT ({})", inner.to_sql(params)),
+            Expr::Term(term) => term.to_sql(params),
+        }
+    }
+
+    fn join(items: &[Expr], separator: &str, params: &mut Vec<Value>) -> String {
+        let parts: Vec<String> = items.iter().map(|item| format!("({})", item.to_sql(params))).collect();
+        parts.join(separator)
+    }
+}
+
+fn push_param(params: &mut Vec<Value>, value: Value) -> String {
+    params.push(value);
+    format!("?{}", params.len())
+}
+
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+impl Term {
+    fn to_sql(&self, params: &mut Vec<Value>) -> String {
+        match self {
+            Term::Text(text) | Term::Phrase(text) => {
+                // bookmarks_fts 中已经包含文件夹路径和标签文本
+                let p = push_param(params, Value::Text(format!("%{}%", escape_like(text))));
+                format!(
+                    "bookmarks.id IN (SELECT rowid FROM bookmarks_fts
+                        WHERE title LIKE {p} ESCAPE '\\' OR url LIKE {p} ESCAPE '\\'
+                           OR folder LIKE {p} ESCAPE '\\' OR notes LIKE {p} ESCAPE '\\'
+                           OR tags LIKE {p} ESCAPE '\\')"
+                )
+            }
+            Term::Tag(name) => {
+                let p = push_param(params, Value::Text(name.clone()));
+                format!(
+                    "EXISTS (SELECT 1 FROM bookmark_tags bt JOIN tags t ON t.id = bt.tag_id
+                        WHERE bt.bookmark_id = bookmarks.id AND t.name = {p})"
+                )
+            }
+            Term::Folder(path) => {
+                let p = push_param(params, Value::Text(path.clone()));
+                format!(
+                    "bookmarks.folder_id IN (SELECT id FROM folder_paths
+                        WHERE path = {p} OR substr(path, 1, length({p}) + 1) = {p} || '/')"
+                )
+            }
+            Term::Site(host) => {
+                // 匹配主机名本身及其子域名
+                let exact = push_param(params, Value::Text(host.clone()));
+                let sub = push_param(params, Value::Text(format!("%.{}", escape_like(host))));
+                format!("(bookmarks.host = {exact} OR bookmarks.host LIKE {sub} ESCAPE '\\')")
+            }
+            Term::Visited(cmp, count) => {
+                let p = push_param(params, Value::Integer(*count));
+                format!("COALESCE(bookmarks.visit_count, 0) {} {p}", cmp.as_sql())
+            }
+            Term::Created(cmp, date) => {
+                let p = push_param(params, Value::Text(date.format("%Y-%m-%d").to_string()));
+                format!("substr(bookmarks.created_at, 1, 10) {} {p}", cmp.as_sql())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(word: &str) -> Expr {
+        Expr::Term(Term::Text(word.into()))
+    }
+
+    fn parse_ok(input: &str) -> Expr {
+        parse(input).unwrap().unwrap()
+    }
+
+    fn parse_err(input: &str) -> (usize, String) {
+        let e = parse(input).unwrap_err();
+        (e.position, e.message)
+    }
+
+    #[test]
+    fn tokenizes_fields_and_negation() {
+        assert_eq!(
+            tokenize(r#"rust -tag:旧 folder:"a b" https://x"#).unwrap(),
+            [
+                (0, Token::Word("rust".into())),
+                (5, Token::Not),
+                (6, Token::Field("tag".into(), "旧".into())),
+                (12, Token::Field("folder".into(), "a b".into())),
+                (25, Token::Word("https://x".into())),
+            ]
+        );
+        // 单独的 - 不是取反
+        assert_eq!(tokenize("a - b").unwrap()[1], (2, Token::Word("-".into())));
+    }
+
+    #[test]
+    fn field_filters() {
+        assert_eq!(parse_ok("TAG:rust"), Expr::Term(Term::Tag("rust".into())));
+        assert_eq!(parse_ok("folder:/工作/项目/"), Expr::Term(Term::Folder("工作/项目".into())));
+        assert_eq!(parse_ok(r#"folder:"a b""#), Expr::Term(Term::Folder("a b".into())));
+        assert_eq!(parse_ok("site:GitHub.com"), Expr::Term(Term::Site("github.com".into())));
+        assert_eq!(parse_ok("visited:>5"), Expr::Term(Term::Visited(Comparison::Gt, 5)));
+        assert_eq!(parse_ok("visited:3"), Expr::Term(Term::Visited(Comparison::Eq, 3)));
+        assert_eq!(
+            parse_ok("created:<2024-01-31"),
+            Expr::Term(Term::Created(Comparison::Lt, NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()))
+        );
+        assert_eq!(
+            parse_ok("-tag:old"),
+            Expr::Not(Box::new(Expr::Term(Term::Tag("old".into()))))
+        );
+        assert_eq!(parse_ok(r#""exact phrase""#), Expr::Term(Term::Phrase("exact phrase".into())));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            parse_ok("a b OR c"),
+            Expr::Or(vec![Expr::And(vec![text("a"), text("b")]), text("c")])
+        );
+        assert_eq!(
+            parse_ok("a (b OR c)"),
+            Expr::And(vec![text("a"), Expr::Or(vec![text("b"), text("c")])])
+        );
+        // 小写的 or 是普通关键词
+        assert_eq!(parse_ok("a or b"), Expr::And(vec![text("a"), text("or"), text("b")]));
+    }
+
+    #[test]
+    fn empty_query() {
+        assert_eq!(parse("   ").unwrap(), None);
+    }
+
+    #[test]
+    fn error_positions() {
+        assert_eq!(parse_err(r#"rust "unclosed"#), (5, "引号未闭合".into()));
+        assert_eq!(parse_err(r#"folder:"a b"#), (7, "引号未闭合".into()));
+        assert_eq!(parse_err("a b)"), (3, "多余的右括号".into()));
+        assert_eq!(parse_err("(a b"), (0, "括号未闭合".into()));
+        assert_eq!(parse_err("OR a"), (0, "OR 前后都需要搜索条件".into()));
+        assert_eq!(parse_err("a OR"), (4, "缺少搜索条件".into()));
+        assert_eq!(parse_err("a OR OR b"), (5, "OR 前后都需要搜索条件".into()));
+        assert_eq!(parse_err("x tag:"), (2, "tag: 缺少值".into()));
+        assert_eq!(parse_err("visited:many").0, 0);
+        assert_eq!(parse_err("a created:2024-13-01").0, 2);
+    }
+
+    #[test]
+    fn limits_nesting() {
+        let nested = |depth: usize| format!("{}a{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(parse(&nested(MAX_DEPTH)).is_ok());
+        assert_eq!(parse_err(&nested(MAX_DEPTH + 1)).0, MAX_DEPTH);
+        assert!(parse(&format!("{}a", "-".repeat(MAX_DEPTH))).is_ok());
+
+        // 很深的嵌套返回错误，而不是栈溢出
+        let (position, message) = parse_err(&nested(5000));
+        assert_eq!(position, MAX_DEPTH);
+        assert_eq!(message, format!("括号和 - 最多嵌套 {} 层", MAX_DEPTH));
+        assert!(parse(&format!("{}a", "-".repeat(5000))).is_err());
+    }
+
+    #[test]
+    fn plain_words_only_for_keywords() {
+        assert_eq!(parse_ok("a b").plain_words(), Some(vec!["a", "b"]));
+        assert_eq!(parse_ok("a tag:b").plain_words(), None);
+        assert_eq!(parse_ok("a OR b").plain_words(), None);
+    }
+
+    #[test]
+    fn to_sql_numbers_params_in_order() {
+        let mut params = Vec::new();
+        let sql = parse_ok("tag:rust -site:example.com visited:>=3").to_sql(&mut params);
+        assert_eq!(
+            params,
+            [
+                Value::Text("rust".into()),
+                Value::Text("example.com".into()),
+                Value::Text("%.example.com".into()),
+                Value::Integer(3),
+            ]
+        );
+        let first = |p: &str| sql.find(p).unwrap();
+        assert!(first("?1") < first("?2") && first("?2") < first("?3") && first("?3") < first("?4"));
+        assert!(sql.contains("NOT ((bookmarks.host = ?2 OR bookmarks.host LIKE ?3"));
+        assert!(sql.ends_with("COALESCE(bookmarks.visit_count, 0) >= ?4)"));
+    }
+
+    #[test]
+    fn text_params_escape_like_wildcards() {
+        let mut params = Vec::new();
+        parse_ok("100%_off").to_sql(&mut params);
+        assert_eq!(params, [Value::Text("%100\\%\\_off%".into())]);
+    }
+}
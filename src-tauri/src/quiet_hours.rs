@@ -0,0 +1,189 @@
+use crate::models::{QuietHours, QuietWindow};
+use crate::schedule;
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+
+/// 首尾相接的时段（包括暂停）最多连续合并的次数
+const MAX_CHAINED: usize = 8;
+
+/// 检查免打扰设置，错误信息直接显示给用户
+pub fn validate(quiet: &QuietHours) -> Result<()> {
+    for window in &quiet.windows {
+        let start = schedule::parse_clock(&window.start)
+            .map_err(|_| anyhow!("免打扰时间格式应为 HH:MM: {}", window.start))?;
+        let end = schedule::parse_clock(&window.end)
+            .map_err(|_| anyhow!("免打扰时间格式应为 HH:MM: {}", window.end))?;
+        if start == end {
+            bail!("免打扰的开始和结束时间不能相同: {}", window.start);
+        }
+    }
+    if let Some(until) = &quiet.paused_until {
+        DateTime::parse_from_rfc3339(until).map_err(|_| anyhow!("暂停时间格式无效: {}", until))?;
+    }
+    Ok(())
+}
+
+/// now 处于免打扰时段或暂停期间时，返回免打扰结束的时间（时段按系统时区）
+pub fn quiet_until(quiet: &QuietHours, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    quiet_until_in(quiet, now, &Local)
+}
+
+pub fn quiet_until_in<Z: TimeZone>(quiet: &QuietHours, now: DateTime<Utc>, tz: &Z) -> Option<DateTime<Utc>> {
+    let paused = quiet
+        .paused_until
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Utc));
+
+    // 一个时段结束时可能正好进入另一个时段，一直顺延到真正可以提醒的时间
+    let mut until = now;
+    for _ in 0..MAX_CHAINED {
+        let end = quiet
+            .windows
+            .iter()
+            .filter_map(|window| window_end(window, until, tz))
+            .chain(paused.filter(|paused| *paused > until))
+            .max();
+        match end {
+            Some(end) => until = end,
+            None => break,
+        }
+    }
+    (until > now).then_some(until)
+}
+
+// 包含 at 的那一次时段的结束时间，at 不在时段内时返回 None
+fn window_end<Z: TimeZone>(window: &QuietWindow, at: DateTime<Utc>, tz: &Z) -> Option<DateTime<Utc>> {
+    let start = schedule::parse_clock(&window.start).ok()?;
+    let end = schedule::parse_clock(&window.end).ok()?;
+    let local = at.with_timezone(tz).naive_local();
+    let time = local.time();
+
+    let end_date = if start < end {
+        if time < start || time >= end {
+            return None;
+        }
+        local.date()
+    } else if time >= start {
+        // 跨过午夜的时段，在当天晚上开始，第二天结束
+        local.date() + Duration::days(1)
+    } else if time < end {
+        local.date()
+    } else {
+        return None;
+    };
+    schedule::resolve_in(tz, end_date.and_time(end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+    use chrono_tz::{America::New_York, Asia::Shanghai, Tz};
+
+    fn quiet(windows: &[(&str, &str)], paused_until: Option<&str>) -> QuietHours {
+        QuietHours {
+            windows: windows
+                .iter()
+                .map(|(start, end)| QuietWindow { start: start.to_string(), end: end.to_string() })
+                .collect(),
+            paused_until: paused_until.map(str::to_string),
+            ..QuietHours::default()
+        }
+    }
+
+    // 以 tz 的本地时间 "2026-03-02 23:00" 计算，结果也按本地时间显示
+    fn until_in(quiet: &QuietHours, local: &str, tz: Tz) -> Option<String> {
+        let naive = NaiveDateTime::parse_from_str(local, "%Y-%m-%d %H:%M").unwrap();
+        let now = tz.from_local_datetime(&naive).unwrap().with_timezone(&Utc);
+        quiet_until_in(quiet, now, &tz).map(|t| t.with_timezone(&tz).format("%Y-%m-%d %H:%M").to_string())
+    }
+
+    fn until(quiet: &QuietHours, local: &str) -> Option<String> {
+        until_in(quiet, local, Shanghai)
+    }
+
+    #[test]
+    fn single_windows() {
+        let night = quiet(&[("22:00", "07:00")], None);
+        assert_eq!(until(&night, "2026-03-02 21:59"), None);
+        assert_eq!(until(&night, "2026-03-02 22:00").as_deref(), Some("2026-03-03 07:00"));
+        assert_eq!(until(&night, "2026-03-02 23:30").as_deref(), Some("2026-03-03 07:00"));
+        // 跨过午夜后在当天结束
+        assert_eq!(until(&night, "2026-03-03 03:00").as_deref(), Some("2026-03-03 07:00"));
+        assert_eq!(until(&night, "2026-03-03 07:00"), None);
+
+        let lunch = quiet(&[("12:00", "13:00")], None);
+        assert_eq!(until(&lunch, "2026-03-02 11:59"), None);
+        assert_eq!(until(&lunch, "2026-03-02 12:30").as_deref(), Some("2026-03-02 13:00"));
+        assert_eq!(until(&lunch, "2026-03-02 13:00"), None);
+
+        assert_eq!(until(&QuietHours::default(), "2026-03-02 12:30"), None);
+    }
+
+    #[test]
+    fn chains_adjacent_windows() {
+        let windows = quiet(&[("07:00", "08:00"), ("22:00", "07:00")], None);
+        assert_eq!(until(&windows, "2026-03-02 23:00").as_deref(), Some("2026-03-03 08:00"));
+
+        // 重叠的时段取最晚的结束时间
+        let overlapping = quiet(&[("22:00", "06:00"), ("23:00", "01:00"), ("05:00", "09:00")], None);
+        assert_eq!(until(&overlapping, "2026-03-02 23:30").as_deref(), Some("2026-03-03 09:00"));
+    }
+
+    #[test]
+    fn pause_until() {
+        // 暂停时间带时区，与系统时区无关
+        let paused = quiet(&[], Some("2026-03-02T18:00:00+08:00"));
+        assert_eq!(until(&paused, "2026-03-02 09:00").as_deref(), Some("2026-03-02 18:00"));
+        assert_eq!(until(&paused, "2026-03-02 18:00"), None);
+        assert_eq!(until(&paused, "2026-03-03 09:00"), None);
+
+        // 暂停结束时正好在时段内，或时段结束时仍在暂停
+        let pause_into_window = quiet(&[("22:00", "07:00")], Some("2026-03-02T23:00:00+08:00"));
+        assert_eq!(until(&pause_into_window, "2026-03-02 20:00").as_deref(), Some("2026-03-03 07:00"));
+        let window_into_pause = quiet(&[("22:00", "07:00")], Some("2026-03-03T09:30:00+08:00"));
+        assert_eq!(until(&window_into_pause, "2026-03-02 23:00").as_deref(), Some("2026-03-03 09:30"));
+
+        // 无法解析的暂停时间被忽略
+        let invalid = quiet(&[], Some("明天"));
+        assert_eq!(until(&invalid, "2026-03-02 09:00"), None);
+    }
+
+    #[test]
+    fn chaining_is_capped() {
+        // 00:00 起每小时一个首尾相接的时段，最多顺延 MAX_CHAINED 次
+        let hours: Vec<(String, String)> =
+            (0..12).map(|h| (format!("{:02}:00", h), format!("{:02}:00", h + 1))).collect();
+        let windows: Vec<(&str, &str)> = hours.iter().map(|(s, e)| (s.as_str(), e.as_str())).collect();
+        let chained = quiet(&windows, None);
+        assert_eq!(
+            until(&chained, "2026-03-02 00:30").as_deref(),
+            Some(format!("2026-03-02 {:02}:00", MAX_CHAINED).as_str())
+        );
+    }
+
+    #[test]
+    fn window_ending_in_dst_gap() {
+        // 2026-03-08 纽约 02:00 跳到 03:00，不存在的 02:30 顺延一小时
+        let windows = quiet(&[("01:00", "02:30")], None);
+        assert_eq!(
+            until_in(&windows, "2026-03-08 01:30", New_York).as_deref(),
+            Some("2026-03-08 03:30")
+        );
+    }
+
+    #[test]
+    fn validate_cases() {
+        assert!(validate(&quiet(&[("22:00", "07:00")], Some("2026-03-02T18:00:00+08:00"))).is_ok());
+        for (bad, expected) in [
+            (quiet(&[("24:00", "07:00")], None), "免打扰时间格式"),
+            (quiet(&[("22:00", "7点")], None), "免打扰时间格式"),
+            (quiet(&[("22:00", "22:00")], None), "不能相同"),
+            (quiet(&[], Some("2026-03-02 18:00")), "暂停时间格式"),
+        ] {
+            let error = validate(&bad).unwrap_err().to_string();
+            assert!(error.contains(expected), "{}", error);
+        }
+    }
+}
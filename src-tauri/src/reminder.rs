@@ -1,159 +1,679 @@
 use crate::database::Database;
-use crate::models::{Bookmark, ReminderFrequency};
-use chrono::{Datelike, DateTime, Duration, Local, NaiveTime, Utc};
+use crate::models::{
+    QuietHoursPolicy, Reminder, ReminderDigest, ReminderEvent, ReminderEventKind,
+    ReminderNotification, ReminderStats,
+};
+use crate::notification::{self, Language};
+use crate::quiet_hours;
+use crate::schedule;
+use chrono::{DateTime, Duration, Utc};
 use tauri::{AppHandle, Emitter};
-use tauri_plugin_notification::NotificationExt;
-use tokio::time::{interval, Duration as TokioDuration};
+use tokio::time::Duration as TokioDuration;
 
-pub struct ReminderService {
-    db: Database,
+// 不指定条数时返回的历史记录数
+const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+// 不指定次数时预览的提醒次数
+const DEFAULT_PREVIEW_COUNT: usize = 5;
+
+/// 当前时间的来源，测试时可以换成可控的时钟
+pub trait Clock: Send + Sync + 'static {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// 提醒到期时的通知方式
+pub trait Notifier: Send + Sync + 'static {
+    fn notify(&self, notification: &ReminderNotification);
+    fn notify_digest(&self, digest: &ReminderDigest);
+}
+
+/// 发送系统通知，并向前端发送 reminder-triggered 或 reminder-digest 事件
+pub struct AppNotifier {
     app_handle: AppHandle,
+    db: Database,
+    language: Language,
+}
+
+impl AppNotifier {
+    pub fn new(app_handle: AppHandle, db: Database, language: Language) -> Self {
+        Self { app_handle, db, language }
+    }
+
+    // 通知按钮的语言，设置修改后立即生效
+    fn language(&self) -> Language {
+        let settings = self.db.get_settings().unwrap_or_default();
+        Language::from_setting(settings.notification_language, self.language)
+    }
+}
+
+impl Notifier for AppNotifier {
+    fn notify(&self, notification: &ReminderNotification) {
+        let actions = notification::show(&self.app_handle, notification, self.language());
+
+        let event = ReminderNotification { actions, ..notification.clone() };
+        let _ = self.app_handle.emit("reminder-triggered", &event);
+    }
+
+    fn notify_digest(&self, digest: &ReminderDigest) {
+        let actions = notification::show_digest(&self.app_handle, digest, self.language());
+
+        let event = ReminderDigest { actions, ..digest.clone() };
+        let _ = self.app_handle.emit("reminder-digest", &event);
+    }
+}
+
+pub struct ReminderService<C: Clock = SystemClock, N: Notifier = AppNotifier> {
+    db: Database,
+    clock: C,
+    notifier: N,
+    language: Language,
 }
 
 impl ReminderService {
     pub fn new(db: Database, app_handle: AppHandle) -> Self {
-        Self { db, app_handle }
+        let language = Language::system();
+        Self::with(db.clone(), SystemClock, AppNotifier::new(app_handle, db, language), language)
     }
+}
 
-    pub async fn start(&self) {
-        let db = self.db.clone();
-        let app_handle = self.app_handle.clone();
+impl<C: Clock, N: Notifier> ReminderService<C, N> {
+    /// language 为设置中没有选择通知语言时使用的语言
+    pub fn with(db: Database, clock: C, notifier: N, language: Language) -> Self {
+        Self { db, clock, notifier, language }
+    }
 
-        tokio::spawn(async move {
-            let mut interval = interval(TokioDuration::from_secs(60));
+    /// 按每个提醒保存的 next_reminder 调度：处理已到期的提醒，然后休眠到最近的下一次，
+    /// 提醒或应用设置有变化时提前醒来重新计算。最长休眠时间见 Settings::poll_interval_minutes
+    pub async fn start(self) {
+        let changes = self.db.reminder_changes();
 
+        tokio::spawn(async move {
             loop {
-                interval.tick().await;
-
-                if let Ok(bookmarks) = db.get_bookmarks_with_reminders() {
-                    for bookmark in bookmarks {
-                        if let Some(reminder) = &bookmark.reminder {
-                            if reminder.enabled && Self::should_remind(&bookmark) {
-                                Self::send_notification(&app_handle, &bookmark);
-                            }
-                        }
-                    }
+                let next_due = self.tick();
+                let settings = self.db.get_settings().unwrap_or_default();
+                let max_sleep = TokioDuration::from_secs(u64::from(settings.poll_interval_minutes) * 60);
+                let sleep = next_due
+                    .map(|due| (due - self.clock.now()).to_std().unwrap_or(TokioDuration::ZERO))
+                    .unwrap_or(max_sleep)
+                    .min(max_sleep);
+
+                tokio::select! {
+                    _ = tokio::time::sleep(sleep) => {}
+                    _ = changes.notified() => {}
                 }
             }
         });
     }
 
-    fn should_remind(bookmark: &Bookmark) -> bool {
-        let reminder = match &bookmark.reminder {
-            Some(r) => r,
-            None => return false,
-        };
+    /// 触发所有到期的提醒并保存新的安排，返回最近的下一次提醒时间。
+    ///
+    /// 免打扰期间到期的提醒按设置推迟到免打扰结束，或者不提醒直接安排下一次。
+    /// 开启提醒摘要时，这一轮到期的多个提醒合并成一条通知
+    pub fn tick(&self) -> Option<DateTime<Utc>> {
+        let now = self.clock.now();
+        let bookmarks = self.db.get_bookmarks_with_reminders().ok()?;
+        // 设置损坏时按默认设置提醒，错误在设置界面中显示
+        let settings = self.db.get_settings().unwrap_or_default();
+        let quiet = &settings.quiet_hours;
+        let quiet_until = quiet_hours::quiet_until(quiet, now);
+        let language = Language::from_setting(settings.notification_language, self.language);
+        let mut next_due: Option<DateTime<Utc>> = None;
+        // 这一轮到期的通知和各自的触发次数
+        let mut due_notifications: Vec<(ReminderNotification, usize)> = Vec::new();
 
-        let now = Local::now();
-        let current_time = now.time();
-        let current_weekday = now.weekday().num_days_from_sunday() as u8;
+        for bookmark in &bookmarks {
+            for reminder in bookmark.reminders.iter().filter(|r| r.enabled) {
+                let mut reminder = reminder.clone();
+                let id = match reminder.id {
+                    Some(id) => id,
+                    None => continue,
+                };
 
-        let reminder_time = match NaiveTime::parse_from_str(&reminder.time, "%H:%M") {
-            Ok(t) => t,
-            Err(_) => return false,
-        };
+                let before = (reminder.last_reminded.clone(), reminder.next_reminder.clone());
+                let due = reminder.next_reminder.clone();
+                let is_due = due
+                    .as_deref()
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .is_some_and(|t| t <= now);
+                let mut skipped = false;
+                let fires = match quiet_until {
+                    Some(until) if is_due && quiet.policy == QuietHoursPolicy::Defer => {
+                        reminder.next_reminder = Some(until.to_rfc3339());
+                        0
+                    }
+                    // 不提醒：直接安排下一次，last_reminded 不变，一次性提醒顺延到下一天
+                    Some(_) if is_due => {
+                        reminder.next_reminder =
+                            schedule::next_occurrence(&reminder, now).map(|t| t.to_rfc3339());
+                        skipped = true;
+                        0
+                    }
+                    Some(_) => {
+                        schedule::advance(&mut reminder, now);
+                        0
+                    }
+                    None => schedule::advance(&mut reminder, now),
+                };
 
-        let time_diff = if current_time >= reminder_time {
-            current_time - reminder_time
-        } else {
-            return false;
-        };
+                if (reminder.last_reminded.clone(), reminder.next_reminder.clone()) != before {
+                    // 先保存再通知，保存失败时不会反复触发
+                    if self.db.save_reminder_state(&reminder).is_err() {
+                        continue;
+                    }
+                }
+                if skipped {
+                    let _ = self.db.add_reminder_event(id, ReminderEventKind::Skipped, due.as_deref(), now);
+                }
 
-        if time_diff.num_minutes() > 1 {
-            return false;
-        }
+                let next = reminder.next_reminder.as_deref().and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+                if let Some(next) = next {
+                    let next = next.with_timezone(&Utc);
+                    next_due = Some(next_due.map_or(next, |due| due.min(next)));
+                }
 
-        if let Some(last_reminded) = &reminder.last_reminded {
-            if let Ok(last_time) = DateTime::parse_from_rfc3339(last_reminded) {
-                let last_local = last_time.with_timezone(&Local);
-                if last_local.date_naive() == now.date_naive() {
-                    return false;
+                if fires == 0 {
+                    continue;
+                }
+                let streak = self
+                    .db
+                    .reminder_stats(bookmark.id)
+                    .ok()
+                    .and_then(|stats| stats.into_iter().find(|s| s.reminder_id == id))
+                    .map(|s| s.current_streak)
+                    .unwrap_or(0);
+                for _ in 0..fires {
+                    let _ = self.db.add_reminder_event(id, ReminderEventKind::Fired, due.as_deref(), now);
                 }
+                let message = notification::compose(bookmark, &reminder, streak, language, &settings);
+                due_notifications.push((message, fires));
             }
         }
 
-        match &reminder.frequency {
-            ReminderFrequency::Daily => true,
-            ReminderFrequency::Weekly => reminder.days.contains(&current_weekday),
-            ReminderFrequency::Custom { interval_days } => {
-                if let Some(last_reminded) = &reminder.last_reminded {
-                    if let Ok(last_time) = DateTime::parse_from_rfc3339(last_reminded) {
-                        let days_since = (now - last_time.with_timezone(&Local)).num_days();
-                        return days_since >= *interval_days as i64;
-                    }
-                }
-                true
+        self.send(due_notifications, language, settings.reminder_digest);
+        next_due
+    }
+
+    // 摘要中每个提醒只列一次；排除在摘要之外的书签和只有一个提醒到期时照常逐个通知
+    fn send(&self, due_notifications: Vec<(ReminderNotification, usize)>, language: Language, digest: bool) {
+        let (mut grouped, mut single): (Vec<_>, Vec<_>) = due_notifications
+            .into_iter()
+            .partition(|(message, _)| digest && !message.bookmark.exclude_from_digest);
+        if grouped.len() < 2 {
+            single.append(&mut grouped);
+        }
+
+        if !grouped.is_empty() {
+            let items = grouped.into_iter().map(|(message, _)| message).collect();
+            self.notifier.notify_digest(&notification::compose_digest(items, language));
+        }
+        for (message, fires) in single {
+            for _ in 0..fires {
+                self.notifier.notify(&message);
             }
-            ReminderFrequency::Once => reminder.last_reminded.is_none(),
         }
     }
+}
 
-    fn send_notification(app_handle: &AppHandle, bookmark: &Bookmark) {
-        let title = "签到提醒";
-        let body = format!("该去 {} 签到了！", bookmark.title);
+/// 标记完成：取消尚未到期的稍后提醒，从 now 起安排下一次
+pub fn complete(db: &Database, reminder_id: i64, now: DateTime<Utc>) -> anyhow::Result<()> {
+    let mut reminder = db.get_reminder(reminder_id)?;
+    reminder.last_reminded = Some(now.to_rfc3339());
+    reminder.next_reminder = schedule::next_occurrence(&reminder, now).map(|t| t.to_rfc3339());
 
-        let _ = app_handle.notification()
-            .builder()
-            .title(title)
-            .body(&body)
-            .show();
+    db.save_reminder_state(&reminder)?;
+    db.reminder_changes().notify_one();
+    db.add_reminder_event(reminder_id, ReminderEventKind::Completed, None, now)
+}
 
-        let _ = app_handle.emit("reminder-triggered", bookmark);
+/// 稍后提醒：now 之后 minutes 分钟再提醒一次
+pub fn snooze(db: &Database, reminder_id: i64, minutes: i64, now: DateTime<Utc>) -> anyhow::Result<()> {
+    if minutes <= 0 {
+        anyhow::bail!("推迟的分钟数必须大于 0: {}", minutes);
     }
+    let snooze_until = Duration::try_minutes(minutes)
+        .and_then(|delay| now.checked_add_signed(delay))
+        .ok_or_else(|| anyhow::anyhow!("推迟的时间太长: {} 分钟", minutes))?;
+    let mut reminder = db.get_reminder(reminder_id)?;
+    reminder.next_reminder = Some(snooze_until.to_rfc3339());
+
+    db.save_reminder_state(&reminder)?;
+    db.reminder_changes().notify_one();
+    db.add_reminder_event(reminder_id, ReminderEventKind::Snoozed, reminder.next_reminder.as_deref(), now)
 }
 
 #[tauri::command]
-pub async fn mark_reminder_completed(
+pub async fn list_reminders(
     db: tauri::State<'_, Database>,
     bookmark_id: i64,
-) -> Result<(), String> {
-    let bookmark = db.get_bookmark(bookmark_id).map_err(|e| e.to_string())?;
+) -> Result<Vec<Reminder>, String> {
+    db.list_reminders(bookmark_id).map_err(|e| e.to_string())
+}
 
-    if let Some(mut reminder) = bookmark.reminder {
-        reminder.last_reminded = Some(Utc::now().to_rfc3339());
+#[tauri::command]
+pub async fn create_reminder(
+    db: tauri::State<'_, Database>,
+    bookmark_id: i64,
+    reminder: Reminder,
+) -> Result<Reminder, String> {
+    db.create_reminder(bookmark_id, reminder).map_err(|e| e.to_string())
+}
 
-        let update_input = crate::models::UpdateBookmarkInput {
-            id: bookmark_id,
-            title: None,
-            url: None,
-            category: None,
-            tags: None,
-            notes: None,
-            reminder: Some(reminder),
-        };
+#[tauri::command]
+pub async fn update_reminder(
+    db: tauri::State<'_, Database>,
+    id: i64,
+    reminder: Reminder,
+) -> Result<Reminder, String> {
+    db.update_reminder(id, reminder).map_err(|e| e.to_string())
+}
 
-        db.update_bookmark(update_input)
-            .map_err(|e| e.to_string())?;
-    }
+/// 删除提醒，它的历史记录一起删除
+#[tauri::command]
+pub async fn delete_reminder(
+    db: tauri::State<'_, Database>,
+    id: i64,
+) -> Result<(), String> {
+    db.delete_reminder(id).map_err(|e| e.to_string())
+}
 
-    Ok(())
+#[tauri::command]
+pub async fn mark_reminder_completed(
+    db: tauri::State<'_, Database>,
+    reminder_id: i64,
+) -> Result<(), String> {
+    complete(&db, reminder_id, SystemClock.now()).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn snooze_reminder(
     db: tauri::State<'_, Database>,
-    bookmark_id: i64,
+    reminder_id: i64,
     minutes: i64,
 ) -> Result<(), String> {
-    let bookmark = db.get_bookmark(bookmark_id).map_err(|e| e.to_string())?;
+    snooze(&db, reminder_id, minutes, SystemClock.now()).map_err(|e| e.to_string())
+}
+
+/// 关闭提醒但不标记完成，只记录到历史，不影响下一次提醒
+#[tauri::command]
+pub async fn dismiss_reminder(
+    db: tauri::State<'_, Database>,
+    reminder_id: i64,
+) -> Result<(), String> {
+    db.add_reminder_event(reminder_id, ReminderEventKind::Dismissed, None, SystemClock.now())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_reminder_history(
+    db: tauri::State<'_, Database>,
+    bookmark_id: Option<i64>,
+    limit: Option<usize>,
+) -> Result<Vec<ReminderEvent>, String> {
+    db.list_reminder_events(bookmark_id, limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_reminder_stats(
+    db: tauri::State<'_, Database>,
+    bookmark_id: Option<i64>,
+) -> Result<Vec<ReminderStats>, String> {
+    db.reminder_stats(bookmark_id).map_err(|e| e.to_string())
+}
+
+/// 预览提醒设置接下来的几次提醒时间，设置有误时返回错误信息
+#[tauri::command]
+pub async fn preview_reminder_schedule(
+    reminder: Reminder,
+    count: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let times = schedule::preview(&reminder, SystemClock.now(), count.unwrap_or(DEFAULT_PREVIEW_COUNT))
+        .map_err(|e| e.to_string())?;
+    Ok(times.iter().map(|t| t.to_rfc3339()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CatchUpPolicy, CreateBookmarkInput, QuietHours, ReminderFrequency, Settings};
+    use std::sync::{Arc, Mutex};
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc)
+    }
+
+    #[derive(Clone)]
+    struct FakeClock(Arc<Mutex<DateTime<Utc>>>);
+
+    impl FakeClock {
+        fn at(text: &str) -> Self {
+            Self(Arc::new(Mutex::new(utc(text))))
+        }
+
+        fn set(&self, text: &str) {
+            *self.0.lock().unwrap() = utc(text);
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> DateTime<Utc> {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    // 记录发出的通知：单个提醒记书签标题，摘要记 "digest:条数"
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl Recorder {
+        fn take(&self) -> Vec<String> {
+            std::mem::take(&mut *self.0.lock().unwrap())
+        }
+    }
+
+    impl Notifier for Recorder {
+        fn notify(&self, notification: &ReminderNotification) {
+            self.0.lock().unwrap().push(notification.bookmark.title.clone());
+        }
 
-    if let Some(mut reminder) = bookmark.reminder {
-        let snooze_until = Utc::now() + Duration::minutes(minutes);
-        reminder.next_reminder = Some(snooze_until.to_rfc3339());
+        fn notify_digest(&self, digest: &ReminderDigest) {
+            self.0.lock().unwrap().push(format!("digest:{}", digest.items.len()));
+        }
+    }
 
-        let update_input = crate::models::UpdateBookmarkInput {
-            id: bookmark_id,
-            title: None,
-            url: None,
-            category: None,
-            tags: None,
-            notes: None,
-            reminder: Some(reminder),
-        };
+    struct Fixture {
+        db: Database,
+        clock: FakeClock,
+        notifier: Recorder,
+        service: ReminderService<FakeClock, Recorder>,
+    }
 
-        db.update_bookmark(update_input)
-            .map_err(|e| e.to_string())?;
+    // 2026-03-02 是周一
+    fn fixture(now: &str) -> Fixture {
+        let db = Database::new(":memory:").unwrap();
+        let clock = FakeClock::at(now);
+        let notifier = Recorder::default();
+        let service = ReminderService::with(db.clone(), clock.clone(), notifier.clone(), Language::Chinese);
+        Fixture { db, clock, notifier, service }
     }
 
-    Ok(())
+    impl Fixture {
+        // 添加一个书签和它的提醒（按 UTC 计算），返回提醒 id。
+        // 创建时按真实时间安排的下一次清掉，由第一次 tick 按假时钟安排
+        fn add(&self, title: &str, frequency: ReminderFrequency, days: Vec<u8>, catch_up: CatchUpPolicy) -> i64 {
+            let reminder = Reminder {
+                id: None,
+                enabled: true,
+                frequency,
+                time: "09:00".into(),
+                days,
+                last_reminded: None,
+                next_reminder: None,
+                catch_up,
+                timezone: Some("UTC".into()),
+                title_template: None,
+                message_template: None,
+            };
+            let bookmark = self
+                .db
+                .create_bookmark(CreateBookmarkInput {
+                    title: title.into(),
+                    url: format!("https://{}.example.com", title),
+                    folder_id: None,
+                    tags: vec![],
+                    icon_url: None,
+                    notes: None,
+                    keyword: None,
+                    reminders: vec![reminder],
+                    exclude_from_digest: false,
+                })
+                .unwrap();
+            let mut reminder = bookmark.reminders[0].clone();
+            reminder.next_reminder = None;
+            self.db.save_reminder_state(&reminder).unwrap();
+            self.service.tick();
+            reminder.id.unwrap()
+        }
+
+        fn tick_at(&self, now: &str) -> Vec<String> {
+            self.clock.set(now);
+            self.service.tick();
+            self.notifier.take()
+        }
+
+        fn reminder(&self, id: i64) -> Reminder {
+            self.db.get_reminder(id).unwrap()
+        }
+
+        fn next(&self, id: i64) -> Option<DateTime<Utc>> {
+            self.reminder(id).next_reminder.map(|t| utc(&t))
+        }
+
+        fn events(&self) -> Vec<(ReminderEventKind, Option<String>)> {
+            let mut events: Vec<_> = self
+                .db
+                .list_reminder_events(None, 100)
+                .unwrap()
+                .into_iter()
+                .map(|e| (e.kind, e.scheduled_for))
+                .collect();
+            events.reverse();
+            events
+        }
+    }
+
+    #[test]
+    fn daily_fires_once_and_schedules_tomorrow() {
+        let f = fixture("2026-03-02T08:00:00Z");
+        let id = f.add("daily", ReminderFrequency::Daily, vec![], CatchUpPolicy::FireOnce);
+        assert_eq!(f.next(id), Some(utc("2026-03-02T09:00:00Z")));
+
+        f.clock.set("2026-03-02T08:30:00Z");
+        assert_eq!(f.service.tick(), Some(utc("2026-03-02T09:00:00Z")));
+        assert!(f.notifier.take().is_empty());
+
+        assert_eq!(f.tick_at("2026-03-02T09:00:30Z"), ["daily"]);
+        let reminder = f.reminder(id);
+        assert_eq!(reminder.last_reminded.map(|t| utc(&t)), Some(utc("2026-03-02T09:00:30Z")));
+        assert_eq!(f.next(id), Some(utc("2026-03-03T09:00:00Z")));
+        assert_eq!(
+            f.events(),
+            [(ReminderEventKind::Fired, Some("2026-03-02T09:00:00+00:00".into()))]
+        );
+
+        // 同一期不会重复提醒
+        assert!(f.tick_at("2026-03-02T09:05:00Z").is_empty());
+    }
+
+    #[test]
+    fn weekly_fires_on_selected_days() {
+        let f = fixture("2026-03-02T08:00:00Z");
+        // 周一和周三
+        let id = f.add("weekly", ReminderFrequency::Weekly, vec![1, 3], CatchUpPolicy::FireOnce);
+        assert_eq!(f.tick_at("2026-03-02T09:00:10Z"), ["weekly"]);
+        assert_eq!(f.next(id), Some(utc("2026-03-04T09:00:00Z")));
+        assert!(f.tick_at("2026-03-03T09:00:10Z").is_empty());
+        assert_eq!(f.tick_at("2026-03-04T09:00:10Z"), ["weekly"]);
+        assert_eq!(f.next(id), Some(utc("2026-03-09T09:00:00Z")));
+    }
+
+    #[test]
+    fn custom_interval_counts_from_last_reminder() {
+        let f = fixture("2026-03-02T08:00:00Z");
+        let id = f.add("custom", ReminderFrequency::Custom { interval_days: 3 }, vec![], CatchUpPolicy::FireOnce);
+        assert_eq!(f.tick_at("2026-03-02T09:00:10Z"), ["custom"]);
+        assert_eq!(f.next(id), Some(utc("2026-03-05T09:00:00Z")));
+        assert_eq!(f.tick_at("2026-03-05T09:00:10Z"), ["custom"]);
+        assert_eq!(f.next(id), Some(utc("2026-03-08T09:00:00Z")));
+    }
+
+    #[test]
+    fn once_fires_a_single_time() {
+        let f = fixture("2026-03-02T08:00:00Z");
+        let id = f.add("once", ReminderFrequency::Once, vec![], CatchUpPolicy::FireOnce);
+        assert_eq!(f.tick_at("2026-03-02T09:00:10Z"), ["once"]);
+        assert_eq!(f.next(id), None);
+        assert!(f.reminder(id).last_reminded.is_some());
+        assert!(f.tick_at("2026-03-03T09:00:10Z").is_empty());
+    }
+
+    #[test]
+    fn rrule_uses_the_rule() {
+        let f = fixture("2026-03-01T08:00:00Z");
+        let rule = ReminderFrequency::Rrule { rule: "FREQ=MONTHLY;BYDAY=1MO".into() };
+        let id = f.add("rrule", rule, vec![], CatchUpPolicy::FireOnce);
+        assert_eq!(f.next(id), Some(utc("2026-03-02T09:00:00Z")));
+        assert_eq!(f.tick_at("2026-03-02T09:00:10Z"), ["rrule"]);
+        assert_eq!(f.next(id), Some(utc("2026-04-06T09:00:00Z")));
+    }
+
+    #[test]
+    fn disabled_reminder_does_not_fire() {
+        let f = fixture("2026-03-02T08:00:00Z");
+        let id = f.add("off", ReminderFrequency::Daily, vec![], CatchUpPolicy::FireOnce);
+        let mut reminder = f.reminder(id);
+        reminder.enabled = false;
+        f.db.update_reminder(id, reminder).unwrap();
+        assert!(f.tick_at("2026-03-02T09:00:10Z").is_empty());
+    }
+
+    #[test]
+    fn within_grace_period_is_not_missed() {
+        let f = fixture("2026-03-02T08:00:00Z");
+        f.add("late", ReminderFrequency::Daily, vec![], CatchUpPolicy::Skip);
+        assert_eq!(f.tick_at("2026-03-02T09:01:30Z"), ["late"]);
+    }
+
+    // 错过了 3 月 2、3、4 日三次，4 日 12:00 醒来
+    fn missed(catch_up: CatchUpPolicy) -> (Fixture, i64, Vec<String>) {
+        let f = fixture("2026-03-02T08:00:00Z");
+        let id = f.add("missed", ReminderFrequency::Daily, vec![], catch_up);
+        let fired = f.tick_at("2026-03-04T12:00:00Z");
+        (f, id, fired)
+    }
+
+    #[test]
+    fn missed_fire_once() {
+        let (f, id, fired) = missed(CatchUpPolicy::FireOnce);
+        assert_eq!(fired, ["missed"]);
+        assert_eq!(f.next(id), Some(utc("2026-03-05T09:00:00Z")));
+        assert_eq!(f.events().len(), 1);
+    }
+
+    #[test]
+    fn missed_skip() {
+        let (f, id, fired) = missed(CatchUpPolicy::Skip);
+        assert!(fired.is_empty());
+        assert_eq!(f.next(id), Some(utc("2026-03-05T09:00:00Z")));
+        assert_eq!(f.reminder(id).last_reminded, None);
+        assert!(f.events().is_empty());
+    }
+
+    #[test]
+    fn missed_fire_all() {
+        let (f, id, fired) = missed(CatchUpPolicy::FireAll);
+        assert_eq!(fired, ["missed", "missed", "missed"]);
+        assert_eq!(f.next(id), Some(utc("2026-03-05T09:00:00Z")));
+        assert_eq!(f.events().len(), 3);
+    }
+
+    #[test]
+    fn snooze_fires_again_then_resumes_schedule() {
+        let f = fixture("2026-03-02T08:00:00Z");
+        let id = f.add("snooze", ReminderFrequency::Daily, vec![], CatchUpPolicy::FireOnce);
+        assert_eq!(f.tick_at("2026-03-02T09:00:10Z"), ["snooze"]);
+
+        snooze(&f.db, id, 10, utc("2026-03-02T09:01:00Z")).unwrap();
+        assert_eq!(f.next(id), Some(utc("2026-03-02T09:11:00Z")));
+        assert!(f.tick_at("2026-03-02T09:10:00Z").is_empty());
+        assert_eq!(f.tick_at("2026-03-02T09:11:05Z"), ["snooze"]);
+        assert_eq!(f.next(id), Some(utc("2026-03-03T09:00:00Z")));
+
+        let kinds: Vec<_> = f.events().into_iter().map(|(kind, _)| kind).collect();
+        assert_eq!(
+            kinds,
+            [ReminderEventKind::Fired, ReminderEventKind::Snoozed, ReminderEventKind::Fired]
+        );
+    }
+
+    #[test]
+    fn snooze_rejects_invalid_minutes() {
+        let f = fixture("2026-03-02T08:00:00Z");
+        let id = f.add("snooze", ReminderFrequency::Daily, vec![], CatchUpPolicy::FireOnce);
+        for minutes in [0, -5, i64::MAX] {
+            assert!(snooze(&f.db, id, minutes, utc("2026-03-02T09:01:00Z")).is_err(), "{}", minutes);
+        }
+        assert_eq!(f.next(id), Some(utc("2026-03-02T09:00:00Z")));
+        assert!(f.events().is_empty());
+    }
+
+    #[test]
+    fn complete_cancels_snooze_and_counts_streak() {
+        let f = fixture("2026-03-02T08:00:00Z");
+        let id = f.add("done", ReminderFrequency::Daily, vec![], CatchUpPolicy::FireOnce);
+        assert_eq!(f.tick_at("2026-03-02T09:00:10Z"), ["done"]);
+        snooze(&f.db, id, 10, utc("2026-03-02T09:01:00Z")).unwrap();
+
+        complete(&f.db, id, utc("2026-03-02T09:05:00Z")).unwrap();
+        assert_eq!(f.next(id), Some(utc("2026-03-03T09:00:00Z")));
+        assert!(f.tick_at("2026-03-02T09:11:05Z").is_empty());
+
+        assert_eq!(f.tick_at("2026-03-03T09:00:10Z"), ["done"]);
+        complete(&f.db, id, utc("2026-03-03T09:30:00Z")).unwrap();
+        let stats = f.db.reminder_stats(None).unwrap();
+        assert_eq!(stats[0].completed, 2);
+        assert_eq!(stats[0].current_streak, 2);
+        assert_eq!(stats[0].snoozed, 1);
+    }
+
+    // 用暂停代替每天的时段，与系统时区无关
+    fn pause_until(f: &Fixture, policy: QuietHoursPolicy, until: &str) {
+        let quiet_hours = QuietHours { windows: vec![], policy, paused_until: Some(until.into()) };
+        f.db.update_settings(&Settings { quiet_hours, ..Default::default() }).unwrap();
+    }
+
+    #[test]
+    fn quiet_hours_defer_fires_when_pause_ends() {
+        let f = fixture("2026-03-02T08:00:00Z");
+        let id = f.add("defer", ReminderFrequency::Daily, vec![], CatchUpPolicy::FireOnce);
+        pause_until(&f, QuietHoursPolicy::Defer, "2026-03-02T10:00:00Z");
+
+        assert!(f.tick_at("2026-03-02T09:00:10Z").is_empty());
+        assert_eq!(f.next(id), Some(utc("2026-03-02T10:00:00Z")));
+        assert_eq!(f.tick_at("2026-03-02T10:00:05Z"), ["defer"]);
+        assert_eq!(f.next(id), Some(utc("2026-03-03T09:00:00Z")));
+    }
+
+    #[test]
+    fn quiet_hours_drop_skips_without_consuming() {
+        let f = fixture("2026-03-02T08:00:00Z");
+        let id = f.add("quiet", ReminderFrequency::Once, vec![], CatchUpPolicy::FireOnce);
+        pause_until(&f, QuietHoursPolicy::Drop, "2026-03-02T10:00:00Z");
+
+        assert!(f.tick_at("2026-03-02T09:00:10Z").is_empty());
+        let reminder = f.reminder(id);
+        assert_eq!(reminder.last_reminded, None);
+        assert_eq!(f.next(id), Some(utc("2026-03-03T09:00:00Z")));
+        assert_eq!(
+            f.events(),
+            [(ReminderEventKind::Skipped, Some("2026-03-02T09:00:00+00:00".into()))]
+        );
+    }
+
+    #[test]
+    fn digest_groups_reminders_due_together() {
+        let f = fixture("2026-03-02T08:00:00Z");
+        f.db.update_settings(&Settings { reminder_digest: true, ..Default::default() }).unwrap();
+        f.add("a", ReminderFrequency::Daily, vec![], CatchUpPolicy::FireOnce);
+        f.add("b", ReminderFrequency::Daily, vec![], CatchUpPolicy::FireOnce);
+        assert_eq!(f.tick_at("2026-03-02T09:00:10Z"), ["digest:2"]);
+    }
 }
@@ -0,0 +1,596 @@
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc, Weekday};
+
+// 查找下一次时最多检查的周期数，避免永远不会匹配的规则死循环
+const MAX_PERIODS: usize = 10_000;
+
+/// RFC 5545 RRULE 的一个子集，例如：
+///
+/// - `FREQ=MONTHLY;BYDAY=1MO`：每月第一个周一
+/// - `FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=9,18;BYMINUTE=0`：工作日 09:00 和 18:00
+/// - `FREQ=HOURLY;INTERVAL=6`：每 6 小时
+/// - `FREQ=DAILY;UNTIL=20261231`：每天，到年底为止
+///
+/// 可以用 `DTSTART:20260105T090000` 或 `DTSTART=...` 指定起点（本地时间），
+/// 没有起点时提醒时间取自提醒设置，INTERVAL 从 1970-01-01 起算
+#[derive(Debug, Clone)]
+pub struct RecurrenceRule {
+    freq: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<Until>,
+    start: Option<NaiveDateTime>,
+    anchor: NaiveDateTime,
+    by_month: Vec<u32>,
+    by_month_day: Vec<i32>,
+    by_day: Vec<(Option<i32>, Weekday)>,
+    by_hour: Vec<u32>,
+    by_minute: Vec<u32>,
+    by_set_pos: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frequency {
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Until {
+    Local(NaiveDateTime),
+    Utc(DateTime<Utc>),
+}
+
+impl RecurrenceRule {
+    /// 解析规则，default_time 为没有 DTSTART 时使用的提醒时间
+    pub fn parse(text: &str, default_time: NaiveTime) -> Result<Self> {
+        let mut freq = None;
+        let mut interval = 1;
+        let mut count = None;
+        let mut until = None;
+        let mut start = None;
+        let mut by_month = Vec::new();
+        let mut by_month_day = Vec::new();
+        let mut by_day = Vec::new();
+        let mut by_hour = Vec::new();
+        let mut by_minute = Vec::new();
+        let mut by_set_pos = Vec::new();
+
+        // 各部分之间可以用分号、空格或换行分隔
+        for token in text.split_whitespace() {
+            let upper = token.to_ascii_uppercase();
+            if upper.starts_with("DTSTART;") {
+                bail!("DTSTART 使用本地时间，不支持 TZID 等参数");
+            }
+            let rule = upper.strip_prefix("RRULE:").unwrap_or(&upper).replacen("DTSTART:", "DTSTART=", 1);
+
+            for part in rule.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+                let (key, value) = part
+                    .split_once('=')
+                    .ok_or_else(|| anyhow!("RRULE 参数格式应为 名称=值: {}", part))?;
+                match key {
+                    "FREQ" => {
+                        freq = Some(match value {
+                            "HOURLY" => Frequency::Hourly,
+                            "DAILY" => Frequency::Daily,
+                            "WEEKLY" => Frequency::Weekly,
+                            "MONTHLY" => Frequency::Monthly,
+                            "YEARLY" => Frequency::Yearly,
+                            "MINUTELY" | "SECONDLY" => bail!("不支持按分钟或秒重复"),
+                            _ => bail!("未知的 FREQ: {}", value),
+                        })
+                    }
+                    "INTERVAL" => interval = parse_number(key, value, 1, 10_000)?,
+                    "COUNT" => count = Some(parse_number(key, value, 1, 100_000)?),
+                    "UNTIL" => until = Some(parse_until(value)?),
+                    "DTSTART" => start = Some(parse_start(value)?),
+                    "BYMONTH" => by_month = parse_list(key, value, 1, 12)?,
+                    "BYMONTHDAY" => by_month_day = parse_signed_list(key, value, 31)?,
+                    "BYDAY" => by_day = value.split(',').map(parse_weekday).collect::<Result<_>>()?,
+                    "BYHOUR" => by_hour = parse_list(key, value, 0, 23)?,
+                    "BYMINUTE" => by_minute = parse_list(key, value, 0, 59)?,
+                    "BYSETPOS" => by_set_pos = parse_signed_list(key, value, 366)?,
+                    "BYSECOND" if value == "0" => {}
+                    "WKST" if value == "MO" => {}
+                    "WKST" => bail!("只支持 WKST=MO"),
+                    _ => bail!("不支持的 RRULE 参数: {}", key),
+                }
+            }
+        }
+
+        let freq = freq.ok_or_else(|| anyhow!("RRULE 缺少 FREQ"))?;
+        // 有 COUNT 时要从起点数起，起点太早会数很久
+        if start.is_some_and(|start| start.year() < 1970) {
+            bail!("DTSTART 不能早于 1970 年");
+        }
+        if count.is_some() && until.is_some() {
+            bail!("COUNT 和 UNTIL 不能同时使用");
+        }
+        if count.is_some() && start.is_none() {
+            bail!("使用 COUNT 时需要指定 DTSTART");
+        }
+        if start.is_none() {
+            match freq {
+                Frequency::Weekly if by_day.is_empty() => {
+                    bail!("WEEKLY 规则需要 BYDAY 或 DTSTART")
+                }
+                Frequency::Monthly if by_day.is_empty() && by_month_day.is_empty() => {
+                    bail!("MONTHLY 规则需要 BYDAY、BYMONTHDAY 或 DTSTART")
+                }
+                Frequency::Yearly if by_month.is_empty() && by_day.is_empty() && by_month_day.is_empty() => {
+                    bail!("YEARLY 规则需要 BYMONTH、BYMONTHDAY、BYDAY 或 DTSTART")
+                }
+                _ => {}
+            }
+        }
+        if by_day.iter().any(|(n, _)| n.is_some()) {
+            match freq {
+                Frequency::Monthly => {}
+                Frequency::Yearly if !by_month.is_empty() => {}
+                Frequency::Yearly => bail!("YEARLY 规则中带序号的 BYDAY 需要同时指定 BYMONTH"),
+                _ => bail!("只有 MONTHLY 和 YEARLY 规则的 BYDAY 可以带序号"),
+            }
+        }
+
+        let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap_or_default();
+        Ok(RecurrenceRule {
+            freq,
+            interval,
+            count,
+            until,
+            start,
+            anchor: start.unwrap_or_else(|| epoch.and_time(default_time)),
+            by_month,
+            by_month_day,
+            by_day,
+            by_hour,
+            by_minute,
+            by_set_pos,
+        })
+    }
+
+    /// after 之后的第一次。after_local 为 after 对应的本地时间，
+    /// resolve 把本地时间转换为 UTC（处理夏令时）
+    pub fn next_after(
+        &self,
+        after: DateTime<Utc>,
+        after_local: NaiveDateTime,
+        resolve: impl Fn(NaiveDateTime) -> Option<DateTime<Utc>>,
+    ) -> Option<DateTime<Utc>> {
+        let step = self.interval as i64;
+        let current = self.period_of(after_local).max(0) / step;
+        // 有 COUNT 时必须从头数，先数完 after 之前的周期
+        let (first, periods) = if self.count.is_some() {
+            (0, current + MAX_PERIODS as i64)
+        } else {
+            (current * step, MAX_PERIODS as i64)
+        };
+        let mut seen = 0;
+
+        for period in (0..periods).map(|i| first + i * step) {
+            for local in self.occurrences_in(period) {
+                if self.start.is_some_and(|start| local < start) {
+                    continue;
+                }
+                let utc = resolve(local);
+                let ended = match self.until {
+                    Some(Until::Local(until)) => local > until,
+                    Some(Until::Utc(until)) => utc.is_some_and(|t| t > until),
+                    None => false,
+                };
+                if ended {
+                    return None;
+                }
+                if let Some(count) = self.count {
+                    seen += 1;
+                    if seen > count {
+                        return None;
+                    }
+                }
+                if let Some(utc) = utc.filter(|t| *t > after) {
+                    return Some(utc);
+                }
+            }
+        }
+        None
+    }
+
+    /// 时间所在的周期序号，anchor 所在周期为 0
+    fn period_of(&self, local: NaiveDateTime) -> i64 {
+        let anchor = self.anchor;
+        match self.freq {
+            Frequency::Hourly => (local - hour_start(anchor)).num_hours(),
+            Frequency::Daily => (local.date() - anchor.date()).num_days(),
+            Frequency::Weekly => (week_start(local.date()) - week_start(anchor.date())).num_days() / 7,
+            Frequency::Monthly => month_index(local.date()) - month_index(anchor.date()),
+            Frequency::Yearly => (local.year() - anchor.year()) as i64,
+        }
+    }
+
+    /// 第 period 个周期内的所有时间，已排序并应用 BYSETPOS
+    fn occurrences_in(&self, period: i64) -> Vec<NaiveDateTime> {
+        let anchor = self.anchor;
+        let mut times: Vec<NaiveDateTime> = match self.freq {
+            Frequency::Hourly => {
+                let hour = hour_start(anchor) + Duration::hours(period);
+                if !self.matches_date(hour.date(), true)
+                    || !(self.by_hour.is_empty() || self.by_hour.contains(&hour.hour()))
+                {
+                    return Vec::new();
+                }
+                self.minutes()
+                    .into_iter()
+                    .map(|m| hour + Duration::minutes(m as i64))
+                    .collect()
+            }
+            Frequency::Daily => {
+                let date = anchor.date() + Duration::days(period);
+                if self.matches_date(date, true) {
+                    self.at_times(&[date])
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Weekly => {
+                let monday = week_start(anchor.date()) + Duration::weeks(period);
+                let dates: Vec<NaiveDate> = (0..7)
+                    .map(|d| monday + Duration::days(d))
+                    .filter(|date| {
+                        if self.by_day.is_empty() {
+                            date.weekday() == anchor.weekday()
+                        } else {
+                            self.by_day.iter().any(|(_, wd)| *wd == date.weekday())
+                        }
+                    })
+                    .filter(|date| self.matches_date(*date, false))
+                    .collect();
+                self.at_times(&dates)
+            }
+            Frequency::Monthly => {
+                let index = month_index(anchor.date()) + period;
+                let (year, month) = (index.div_euclid(12) as i32, index.rem_euclid(12) as u32 + 1);
+                if !(self.by_month.is_empty() || self.by_month.contains(&month)) {
+                    return Vec::new();
+                }
+                self.at_times(&self.days_in_month(year, month))
+            }
+            Frequency::Yearly => {
+                let year = anchor.year() + period as i32;
+                // 没有 BYMONTH 时，BYMONTHDAY 和 BYDAY 作用于全年每个月
+                let months = if !self.by_month.is_empty() {
+                    self.by_month.clone()
+                } else if self.by_month_day.is_empty() && self.by_day.is_empty() {
+                    vec![anchor.month()]
+                } else {
+                    (1..=12).collect()
+                };
+                let dates: Vec<NaiveDate> = months
+                    .into_iter()
+                    .flat_map(|month| self.days_in_month(year, month))
+                    .collect();
+                self.at_times(&dates)
+            }
+        };
+
+        times.sort();
+        times.dedup();
+        if self.by_set_pos.is_empty() {
+            return times;
+        }
+
+        let mut picked: Vec<NaiveDateTime> = self
+            .by_set_pos
+            .iter()
+            .filter_map(|&pos| {
+                let index = if pos > 0 { pos as i64 - 1 } else { times.len() as i64 + pos as i64 };
+                usize::try_from(index).ok().and_then(|i| times.get(i).copied())
+            })
+            .collect();
+        picked.sort();
+        picked.dedup();
+        picked
+    }
+
+    /// BYMONTH、BYMONTHDAY 以及（不带序号时）BYDAY 的过滤
+    fn matches_date(&self, date: NaiveDate, check_weekday: bool) -> bool {
+        (self.by_month.is_empty() || self.by_month.contains(&date.month()))
+            && (self.by_month_day.is_empty() || self.by_month_day.iter().any(|&d| month_day_matches(date, d)))
+            && (!check_weekday
+                || self.by_day.is_empty()
+                || self.by_day.iter().any(|(_, wd)| *wd == date.weekday()))
+    }
+
+    /// 一个月中符合 BYMONTHDAY 和 BYDAY 的日期，都没有指定时取起点的日期
+    fn days_in_month(&self, year: i32, month: u32) -> Vec<NaiveDate> {
+        let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+            return Vec::new();
+        };
+        if self.by_month_day.is_empty() && self.by_day.is_empty() {
+            // 起点是 31 号时跳过没有 31 号的月份
+            return NaiveDate::from_ymd_opt(year, month, self.anchor.day()).into_iter().collect();
+        }
+
+        let length = days_in_month(first);
+        (0..length)
+            .map(|d| first + Duration::days(d as i64))
+            .filter(|date| {
+                self.by_month_day.is_empty() || self.by_month_day.iter().any(|&d| month_day_matches(*date, d))
+            })
+            .filter(|date| {
+                self.by_day.is_empty()
+                    || self.by_day.iter().any(|&(nth, wd)| {
+                        date.weekday() == wd
+                            && match nth {
+                                None => true,
+                                Some(n) if n > 0 => (date.day() as i32 - 1) / 7 + 1 == n,
+                                Some(n) => (length as i32 - date.day() as i32) / 7 + 1 == -n,
+                            }
+                    })
+            })
+            .collect()
+    }
+
+    fn minutes(&self) -> Vec<u32> {
+        if self.by_minute.is_empty() {
+            vec![self.anchor.minute()]
+        } else {
+            self.by_minute.clone()
+        }
+    }
+
+    fn at_times(&self, dates: &[NaiveDate]) -> Vec<NaiveDateTime> {
+        let hours = if self.by_hour.is_empty() { vec![self.anchor.hour()] } else { self.by_hour.clone() };
+        let minutes = self.minutes();
+
+        let mut times = Vec::new();
+        for date in dates {
+            for &h in &hours {
+                times.extend(minutes.iter().filter_map(|&m| date.and_hms_opt(h, m, 0)));
+            }
+        }
+        times
+    }
+}
+
+fn hour_start(time: NaiveDateTime) -> NaiveDateTime {
+    time.date().and_hms_opt(time.hour(), 0, 0).unwrap_or(time)
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn month_index(date: NaiveDate) -> i64 {
+    date.year() as i64 * 12 + date.month0() as i64
+}
+
+fn days_in_month(first: NaiveDate) -> u32 {
+    let next = match first.month() {
+        12 => NaiveDate::from_ymd_opt(first.year() + 1, 1, 1),
+        month => NaiveDate::from_ymd_opt(first.year(), month + 1, 1),
+    };
+    next.map(|next| (next - first).num_days() as u32).unwrap_or(31)
+}
+
+// 负数表示倒数第几天
+fn month_day_matches(date: NaiveDate, day: i32) -> bool {
+    if day > 0 {
+        date.day() as i32 == day
+    } else {
+        let length = days_in_month(date.with_day(1).unwrap_or(date)) as i32;
+        date.day() as i32 == length + day + 1
+    }
+}
+
+fn parse_number(key: &str, value: &str, min: u32, max: u32) -> Result<u32> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|n| (min..=max).contains(n))
+        .ok_or_else(|| anyhow!("{} 的值应为 {} 到 {} 之间的整数: {}", key, min, max, value))
+}
+
+fn parse_list(key: &str, value: &str, min: u32, max: u32) -> Result<Vec<u32>> {
+    value.split(',').map(|v| parse_number(key, v.trim(), min, max)).collect()
+}
+
+// ±1 到 ±max，不能为 0
+fn parse_signed_list(key: &str, value: &str, max: i32) -> Result<Vec<i32>> {
+    value
+        .split(',')
+        .map(|v| {
+            v.trim()
+                .parse::<i32>()
+                .ok()
+                .filter(|n| *n != 0 && n.abs() <= max)
+                .ok_or_else(|| anyhow!("{} 的值应为 ±1 到 ±{} 之间的整数: {}", key, max, v))
+        })
+        .collect()
+}
+
+// 例如 MO、1MO、-1FR
+fn parse_weekday(value: &str) -> Result<(Option<i32>, Weekday)> {
+    let value = value.trim();
+    // 按字节位置切分，非 ASCII 的输入可能切在字符中间
+    if !value.is_ascii() {
+        bail!("BYDAY 中的星期无效: {}", value);
+    }
+    let split = value.len().saturating_sub(2);
+    let (nth, day) = value.split_at(split);
+    let weekday = match day {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => bail!("BYDAY 中的星期无效: {}", value),
+    };
+    if nth.is_empty() {
+        return Ok((None, weekday));
+    }
+    let nth = nth
+        .trim_start_matches('+')
+        .parse::<i32>()
+        .ok()
+        .filter(|n| *n != 0 && n.abs() <= 53)
+        .ok_or_else(|| anyhow!("BYDAY 中的序号无效: {}", value))?;
+    Ok((Some(nth), weekday))
+}
+
+// 20260105 或 20260105T090000
+fn parse_local(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(value, "%Y%m%d").ok().and_then(|d| d.and_hms_opt(0, 0, 0)))
+}
+
+fn parse_start(value: &str) -> Result<NaiveDateTime> {
+    if value.ends_with('Z') {
+        bail!("DTSTART 使用本地时间，不要带 Z");
+    }
+    parse_local(value).ok_or_else(|| anyhow!("DTSTART 格式应为 YYYYMMDD 或 YYYYMMDDTHHMMSS: {}", value))
+}
+
+fn parse_until(value: &str) -> Result<Until> {
+    let invalid = || anyhow!("UNTIL 格式应为 YYYYMMDD 或 YYYYMMDDTHHMMSS[Z]: {}", value);
+    if let Some(utc) = value.strip_suffix('Z') {
+        let time = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+        return Ok(Until::Utc(time.and_utc()));
+    }
+    if value.contains('T') {
+        return parse_local(value).map(Until::Local).ok_or_else(invalid);
+    }
+    // 只有日期时包含当天
+    NaiveDate::parse_from_str(value, "%Y%m%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(23, 59, 59))
+        .map(Until::Local)
+        .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc)
+    }
+
+    fn nine() -> NaiveTime {
+        NaiveTime::from_hms_opt(9, 0, 0).unwrap()
+    }
+
+    // 按 UTC 计算，不涉及夏令时
+    fn next(rule: &str, after: &str) -> Option<DateTime<Utc>> {
+        let after = utc(after);
+        RecurrenceRule::parse(rule, nine())
+            .unwrap()
+            .next_after(after, after.naive_utc(), |naive| Some(naive.and_utc()))
+    }
+
+    #[test]
+    fn first_monday_of_month() {
+        let rule = "FREQ=MONTHLY;BYDAY=1MO";
+        assert_eq!(next(rule, "2026-03-01T00:00:00Z"), Some(utc("2026-03-02T09:00:00Z")));
+        assert_eq!(next(rule, "2026-03-02T09:00:00Z"), Some(utc("2026-04-06T09:00:00Z")));
+    }
+
+    #[test]
+    fn weekdays_twice_a_day() {
+        let rule = "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=9,18;BYMINUTE=0";
+        // 2026-03-06 是周五
+        assert_eq!(next(rule, "2026-03-06T10:00:00Z"), Some(utc("2026-03-06T18:00:00Z")));
+        assert_eq!(next(rule, "2026-03-06T18:00:00Z"), Some(utc("2026-03-09T09:00:00Z")));
+    }
+
+    #[test]
+    fn every_six_hours() {
+        // 没有 DTSTART 时从 1970-01-01 的提醒时间起算：03、09、15、21 点
+        let rule = "FREQ=HOURLY;INTERVAL=6";
+        assert_eq!(next(rule, "2026-03-02T10:00:00Z"), Some(utc("2026-03-02T15:00:00Z")));
+        assert_eq!(next(rule, "2026-03-02T21:00:00Z"), Some(utc("2026-03-03T03:00:00Z")));
+    }
+
+    #[test]
+    fn until_end_date() {
+        let rule = "FREQ=DAILY;UNTIL=20261231";
+        assert_eq!(next(rule, "2026-12-31T08:00:00Z"), Some(utc("2026-12-31T09:00:00Z")));
+        assert_eq!(next(rule, "2026-12-31T09:00:00Z"), None);
+    }
+
+    #[test]
+    fn count_and_dtstart() {
+        let rule = "DTSTART:20260105T080000 RRULE:FREQ=DAILY;COUNT=3";
+        assert_eq!(next(rule, "2026-01-01T00:00:00Z"), Some(utc("2026-01-05T08:00:00Z")));
+        assert_eq!(next(rule, "2026-01-06T08:00:00Z"), Some(utc("2026-01-07T08:00:00Z")));
+        assert_eq!(next(rule, "2026-01-07T08:00:00Z"), None);
+    }
+
+    #[test]
+    fn count_from_long_ago() {
+        // 从起点到现在已经超过 MAX_PERIODS 个周期
+        let rule = "DTSTART:20200101T000000 RRULE:FREQ=HOURLY;COUNT=100000";
+        assert_eq!(next(rule, "2026-03-02T10:30:00Z"), Some(utc("2026-03-02T11:00:00Z")));
+        // 最后一次（第 50000 次）是 2025-09-14 07:00
+        let rule = "DTSTART:20200101T000000 RRULE:FREQ=HOURLY;COUNT=50000";
+        assert_eq!(next(rule, "2025-09-14T06:30:00Z"), Some(utc("2025-09-14T07:00:00Z")));
+        assert_eq!(next(rule, "2025-09-14T07:00:00Z"), None);
+    }
+
+    #[test]
+    fn yearly_without_by_month() {
+        // BYMONTHDAY 和 BYDAY 作用于每个月，不只是起点所在的月份
+        let rule = "DTSTART:20260115T090000 RRULE:FREQ=YEARLY;BYMONTHDAY=1";
+        assert_eq!(next(rule, "2026-03-15T00:00:00Z"), Some(utc("2026-04-01T09:00:00Z")));
+        // 2026 年最后一个周五是 12-25
+        let rule = "DTSTART:20260101T090000 RRULE:FREQ=YEARLY;BYDAY=FR;BYSETPOS=-1";
+        assert_eq!(next(rule, "2026-03-01T00:00:00Z"), Some(utc("2026-12-25T09:00:00Z")));
+        assert_eq!(next(rule, "2026-12-25T09:00:00Z"), Some(utc("2027-12-31T09:00:00Z")));
+        // 2026-03-03 是周二
+        assert_eq!(next("FREQ=YEARLY;BYDAY=MO", "2026-03-03T00:00:00Z"), Some(utc("2026-03-09T09:00:00Z")));
+    }
+
+    #[test]
+    fn set_pos_and_negative_month_day() {
+        // 每月最后一个工作日，2026-03-31 是周二
+        let rule = "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1";
+        assert_eq!(next(rule, "2026-03-01T00:00:00Z"), Some(utc("2026-03-31T09:00:00Z")));
+        let rule = "FREQ=MONTHLY;BYMONTHDAY=-1";
+        assert_eq!(next(rule, "2026-02-01T00:00:00Z"), Some(utc("2026-02-28T09:00:00Z")));
+    }
+
+    #[test]
+    fn parse_errors() {
+        for rule in [
+            "BYDAY=MO",
+            "FREQ=MINUTELY",
+            "FREQ=WEEKLY",
+            "FREQ=DAILY;COUNT=3",
+            "FREQ=YEARLY",
+            "DTSTART:19691231T090000 RRULE:FREQ=DAILY;COUNT=3",
+            "FREQ=WEEKLY;BYDAY=2MO",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;FOO=1",
+            "FREQ=DAILY;UNTIL=soon",
+            "FREQ=MONTHLY;BYDAY=M日",
+            "FREQ=MONTHLY;BYDAY=日",
+            "FREQ=MONTHLY;BYDAY=1日MO",
+        ] {
+            assert!(RecurrenceRule::parse(rule, nine()).is_err(), "{}", rule);
+        }
+    }
+
+    #[test]
+    fn parse_weekday_with_ordinal() {
+        assert_eq!(parse_weekday("MO").unwrap(), (None, Weekday::Mon));
+        assert_eq!(parse_weekday("+2TU").unwrap(), (Some(2), Weekday::Tue));
+        assert_eq!(parse_weekday("-1FR").unwrap(), (Some(-1), Weekday::Fri));
+        assert!(parse_weekday("0MO").is_err());
+        assert!(parse_weekday("X").is_err());
+    }
+}
@@ -0,0 +1,368 @@
+use crate::models::{CatchUpPolicy, Reminder, ReminderFrequency};
+use crate::notification;
+use crate::rrule::RecurrenceRule;
+use anyhow::{anyhow, bail, Result};
+use chrono::{
+    DateTime, Datelike, Duration, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+};
+use chrono_tz::Tz;
+
+/// 到期后这么久之内都算按时触发，超过后按错过处理
+const GRACE_PERIOD_MINUTES: i64 = 2;
+
+/// FireAll 时最多补发的次数
+const MAX_CATCH_UP: usize = 10;
+
+/// 预览最多返回的次数
+const MAX_PREVIEW: usize = 100;
+
+/// 检查提醒设置能否解析，错误信息直接显示给用户
+pub fn validate(reminder: &Reminder) -> Result<()> {
+    let time = parse_clock(&reminder.time)?;
+    if let Some(name) = &reminder.timezone {
+        name.parse::<Tz>().map_err(|_| anyhow!("未知的时区: {}", name))?;
+    }
+    match &reminder.frequency {
+        ReminderFrequency::Weekly if reminder.days.is_empty() => bail!("每周提醒至少要选择一天"),
+        ReminderFrequency::Weekly if reminder.days.iter().any(|d| *d > 6) => {
+            bail!("星期应为 0（周日）到 6（周六）: {:?}", reminder.days)
+        }
+        ReminderFrequency::Rrule { rule } => {
+            RecurrenceRule::parse(rule, time)?;
+        }
+        _ => {}
+    }
+    for template in [&reminder.title_template, &reminder.message_template].into_iter().flatten() {
+        notification::validate_template(template)?;
+    }
+    Ok(())
+}
+
+/// 从 from 开始接下来的 count 次提醒时间
+pub fn preview(reminder: &Reminder, from: DateTime<Utc>, count: usize) -> Result<Vec<DateTime<Utc>>> {
+    validate(reminder)?;
+
+    let mut probe = reminder.clone();
+    let mut times = Vec::new();
+    let mut after = from;
+    while times.len() < count.min(MAX_PREVIEW) {
+        let Some(next) = next_occurrence(&probe, after) else {
+            break;
+        };
+        times.push(next);
+        probe.last_reminded = Some(next.to_rfc3339());
+        after = next;
+    }
+    Ok(times)
+}
+
+/// 计算 after 之后的下一次提醒时间（提醒的时区），没有下一次时返回 None
+pub fn next_occurrence(reminder: &Reminder, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let time = parse_clock(&reminder.time).ok()?;
+    let zone = Zone::of(reminder);
+    let today = zone.local(after).date();
+    let at = |date: NaiveDate| zone.resolve(date.and_time(time)).filter(|t| *t > after);
+
+    match &reminder.frequency {
+        ReminderFrequency::Daily => (0..=2).find_map(|d| at(today + Duration::days(d))),
+        ReminderFrequency::Weekly => (0..=7)
+            .map(|d| today + Duration::days(d))
+            .filter(|date| reminder.days.contains(&(date.weekday().num_days_from_sunday() as u8)))
+            .find_map(at),
+        ReminderFrequency::Custom { interval_days } => {
+            let interval = (*interval_days).max(1) as i64;
+            let anchor = match reminder.last_reminded.as_deref().and_then(parse_time) {
+                Some(last) => zone.local(last).date(),
+                // 还没有提醒过时从最近的一次开始
+                None => return (0..=2).find_map(|d| at(today + Duration::days(d))),
+            };
+            // 跳过已经过去的周期
+            let mut date = anchor + Duration::days(interval);
+            if date < today {
+                let behind = (today - date).num_days();
+                date += Duration::days((behind + interval - 1) / interval * interval);
+            }
+            (0..3).find_map(|i| at(date + Duration::days(i * interval)))
+        }
+        ReminderFrequency::Once => {
+            if reminder.last_reminded.is_some() {
+                None
+            } else {
+                (0..=2).find_map(|d| at(today + Duration::days(d)))
+            }
+        }
+        ReminderFrequency::Rrule { rule } => RecurrenceRule::parse(rule, time)
+            .ok()?
+            .next_after(after, zone.local(after), |naive| zone.resolve(naive)),
+    }
+}
+
+/// 处理到期的提醒：更新 last_reminded 和 next_reminder，返回需要发送的通知数。
+///
+/// 超过宽限时间才处理的提醒视为错过，按 catch_up 决定补发几次
+pub fn advance(reminder: &mut Reminder, now: DateTime<Utc>) -> usize {
+    let due = match reminder.next_reminder.as_deref().and_then(parse_time) {
+        Some(due) => due,
+        None => {
+            reminder.next_reminder = next_occurrence(reminder, now).map(|t| t.to_rfc3339());
+            return 0;
+        }
+    };
+    if due > now {
+        return 0;
+    }
+
+    let missed = now - due > Duration::minutes(GRACE_PERIOD_MINUTES);
+    let fires = match (missed, reminder.catch_up) {
+        (false, _) | (true, CatchUpPolicy::FireOnce) => 1,
+        (true, CatchUpPolicy::Skip) => 0,
+        (true, CatchUpPolicy::FireAll) => missed_occurrences(reminder, due, now),
+    };
+
+    if fires > 0 {
+        reminder.last_reminded = Some(now.to_rfc3339());
+    }
+    reminder.next_reminder = match reminder.frequency {
+        // 一次性提醒到期后不论是否补发都结束
+        ReminderFrequency::Once => None,
+        _ => next_occurrence(reminder, now).map(|t| t.to_rfc3339()),
+    };
+    fires
+}
+
+/// 保存提醒设置时调用：沿用原来的提醒记录，设置有变化或还没有安排时重新计算下一次。
+///
+/// 修改已有的提醒时 last_reminded 和 next_reminder 只由后台维护，忽略客户端传来的值。
+/// 编辑表单打开期间提醒可能已经触发过，表单里的旧值会让它再触发一次
+pub fn reschedule(old: Option<&Reminder>, reminder: &mut Reminder, now: DateTime<Utc>) {
+    if let Some(old) = old {
+        reminder.last_reminded = old.last_reminded.clone();
+        if reminder.timezone.is_none() {
+            reminder.timezone = old.timezone.clone();
+        }
+        reminder.next_reminder = if same_schedule(old, reminder) {
+            old.next_reminder.clone()
+        } else {
+            None
+        };
+    }
+
+    // 固定时区，之后系统时区变化时提醒时间不变
+    if reminder.timezone.is_none() {
+        reminder.timezone = system_timezone();
+    }
+
+    if !reminder.enabled {
+        reminder.next_reminder = None;
+    } else if reminder.next_reminder.is_none() {
+        reminder.next_reminder = next_occurrence(reminder, now).map(|t| t.to_rfc3339());
+    }
+}
+
+/// 两个提醒的时间设置是否相同
+pub fn same_schedule(a: &Reminder, b: &Reminder) -> bool {
+    a.enabled == b.enabled
+        && a.frequency == b.frequency
+        && a.time == b.time
+        && a.days == b.days
+        && a.timezone == b.timezone
+}
+
+fn missed_occurrences(reminder: &Reminder, due: DateTime<Utc>, now: DateTime<Utc>) -> usize {
+    let mut probe = reminder.clone();
+    let mut at = due;
+    let mut count = 1;
+    while count < MAX_CATCH_UP {
+        probe.last_reminded = Some(at.to_rfc3339());
+        match next_occurrence(&probe, at) {
+            Some(next) if next <= now => {
+                count += 1;
+                at = next;
+            }
+            _ => break,
+        }
+    }
+    count
+}
+
+/// 系统时区的 IANA 名称，无法识别时返回 None
+pub fn system_timezone() -> Option<String> {
+    iana_time_zone::get_timezone().ok().filter(|name| name.parse::<Tz>().is_ok())
+}
+
+/// 计算提醒时间使用的时区，没有设置或无法识别时使用系统时区
+enum Zone {
+    Named(Tz),
+    System,
+}
+
+impl Zone {
+    fn of(reminder: &Reminder) -> Zone {
+        reminder
+            .timezone
+            .as_deref()
+            .and_then(|name| name.parse().ok())
+            .map(Zone::Named)
+            .unwrap_or(Zone::System)
+    }
+
+    fn local(&self, time: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Zone::Named(tz) => time.with_timezone(tz).naive_local(),
+            Zone::System => time.with_timezone(&Local).naive_local(),
+        }
+    }
+
+    /// 本地时间对应的 UTC 时间。夏令时跳过的时间顺延一小时，重复的时间取第一次
+    fn resolve(&self, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Zone::Named(tz) => resolve_in(tz, naive),
+            Zone::System => resolve_in(&Local, naive),
+        }
+    }
+}
+
+/// 本地时间在指定时区对应的 UTC 时间，规则同 Zone::resolve
+pub fn resolve_in<Z: TimeZone>(tz: &Z, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+    let resolve = |naive| match tz.from_local_datetime(&naive) {
+        LocalResult::Single(t) => Some(t.with_timezone(&Utc)),
+        // 不依赖两个结果的顺序，直接取较早的时刻
+        LocalResult::Ambiguous(a, b) => Some(a.with_timezone(&Utc).min(b.with_timezone(&Utc))),
+        LocalResult::None => None,
+    };
+    resolve(naive).or_else(|| resolve(naive + Duration::hours(1)))
+}
+
+pub fn parse_clock(time: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| anyhow!("提醒时间格式应为 HH:MM: {}", time))
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        parse_time(text).unwrap()
+    }
+
+    fn new_york(frequency: ReminderFrequency, time: &str, days: Vec<u8>) -> Reminder {
+        Reminder {
+            id: None,
+            enabled: true,
+            frequency,
+            time: time.into(),
+            days,
+            last_reminded: None,
+            next_reminder: None,
+            catch_up: CatchUpPolicy::FireOnce,
+            timezone: Some("America/New_York".into()),
+            title_template: None,
+            message_template: None,
+        }
+    }
+
+    fn times(reminder: &Reminder, from: &str, count: usize) -> Vec<DateTime<Utc>> {
+        preview(reminder, utc(from), count).unwrap()
+    }
+
+    // 2026-03-08 和 2026-11-01 都是周日
+    fn sunday_rule() -> ReminderFrequency {
+        ReminderFrequency::Rrule { rule: "FREQ=WEEKLY;BYDAY=SU".into() }
+    }
+
+    #[test]
+    fn daily_across_spring_forward() {
+        // 3 月 8 日 02:00 EST 跳到 03:00 EDT，02:30 不存在，顺延到 03:30 EDT
+        let reminder = new_york(ReminderFrequency::Daily, "02:30", vec![]);
+        assert_eq!(
+            times(&reminder, "2026-03-07T00:00:00Z", 3),
+            [
+                utc("2026-03-07T07:30:00Z"),
+                utc("2026-03-08T07:30:00Z"),
+                utc("2026-03-09T06:30:00Z"),
+            ]
+        );
+
+        // 不受影响的时间保持当地时间不变
+        let reminder = new_york(ReminderFrequency::Daily, "09:00", vec![]);
+        assert_eq!(
+            times(&reminder, "2026-03-07T00:00:00Z", 2),
+            [utc("2026-03-07T14:00:00Z"), utc("2026-03-08T13:00:00Z")]
+        );
+    }
+
+    #[test]
+    fn daily_across_fall_back() {
+        // 11 月 1 日 02:00 EDT 回到 01:00 EST，01:30 出现两次，只在第一次提醒
+        let reminder = new_york(ReminderFrequency::Daily, "01:30", vec![]);
+        assert_eq!(
+            times(&reminder, "2026-10-31T00:00:00Z", 3),
+            [
+                utc("2026-10-31T05:30:00Z"),
+                utc("2026-11-01T05:30:00Z"),
+                utc("2026-11-02T06:30:00Z"),
+            ]
+        );
+        // 在第二个 01:30 之前检查也不会再提醒一次
+        assert_eq!(
+            next_occurrence(&reminder, utc("2026-11-01T06:00:00Z")),
+            Some(utc("2026-11-02T06:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn weekly_across_transitions() {
+        let reminder = new_york(ReminderFrequency::Weekly, "02:30", vec![0]);
+        assert_eq!(
+            times(&reminder, "2026-03-02T00:00:00Z", 2),
+            [utc("2026-03-08T07:30:00Z"), utc("2026-03-15T06:30:00Z")]
+        );
+
+        let reminder = new_york(ReminderFrequency::Weekly, "01:30", vec![0]);
+        assert_eq!(
+            times(&reminder, "2026-10-26T00:00:00Z", 2),
+            [utc("2026-11-01T05:30:00Z"), utc("2026-11-08T06:30:00Z")]
+        );
+    }
+
+    #[test]
+    fn weekly_requires_valid_days() {
+        assert!(validate(&new_york(ReminderFrequency::Weekly, "09:00", vec![])).is_err());
+        assert!(validate(&new_york(ReminderFrequency::Weekly, "09:00", vec![7])).is_err());
+        assert!(validate(&new_york(ReminderFrequency::Weekly, "09:00", vec![0, 6])).is_ok());
+        assert!(validate(&new_york(ReminderFrequency::Daily, "09:00", vec![])).is_ok());
+    }
+
+    #[test]
+    fn rrule_across_transitions() {
+        let reminder = new_york(sunday_rule(), "02:30", vec![]);
+        assert_eq!(
+            times(&reminder, "2026-03-02T00:00:00Z", 2),
+            [utc("2026-03-08T07:30:00Z"), utc("2026-03-15T06:30:00Z")]
+        );
+
+        let reminder = new_york(sunday_rule(), "01:30", vec![]);
+        assert_eq!(
+            times(&reminder, "2026-10-26T00:00:00Z", 2),
+            [utc("2026-11-01T05:30:00Z"), utc("2026-11-08T06:30:00Z")]
+        );
+        assert_eq!(
+            next_occurrence(&reminder, utc("2026-11-01T05:30:00Z")),
+            Some(utc("2026-11-08T06:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn due_reminder_in_gap_fires_once() {
+        // 到期时间是顺延后的 03:30 EDT，触发后安排到第二天的 02:30 EDT
+        let mut reminder = new_york(ReminderFrequency::Daily, "02:30", vec![]);
+        reminder.next_reminder = Some("2026-03-08T07:30:00+00:00".into());
+        assert_eq!(advance(&mut reminder, utc("2026-03-08T07:30:30Z")), 1);
+        assert_eq!(reminder.next_reminder.as_deref(), Some("2026-03-09T06:30:00+00:00"));
+        assert_eq!(advance(&mut reminder, utc("2026-03-08T08:00:00Z")), 0);
+    }
+}
@@ -0,0 +1,313 @@
+use crate::database::Database;
+use crate::models::Settings;
+use crate::notification;
+use crate::quiet_hours;
+use anyhow::{bail, Result};
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+/// 提醒服务最长休眠时间的可选范围（分钟）
+const POLL_INTERVAL_RANGE: RangeInclusive<u32> = 1..=60;
+
+// 默认的数据库文件名，在应用数据目录下
+const DEFAULT_DATABASE_FILE: &str = "bookmarks.db";
+
+// 应用数据目录中记录自定义数据库位置的文件。数据库位置不能保存在数据库自己里面
+const DATABASE_LOCATION_FILE: &str = "database_location";
+
+// 设置中更换了、下次启动时才生效的数据库位置，内容为空表示恢复默认位置
+const PENDING_LOCATION_FILE: &str = "database_location.pending";
+
+/// 检查设置，错误信息直接显示给用户
+pub fn validate(settings: &Settings) -> Result<()> {
+    if !POLL_INTERVAL_RANGE.contains(&settings.poll_interval_minutes) {
+        bail!(
+            "提醒检查间隔应在 {} 到 {} 分钟之间",
+            POLL_INTERVAL_RANGE.start(),
+            POLL_INTERVAL_RANGE.end()
+        );
+    }
+    for template in [&settings.default_title_template, &settings.default_message_template]
+        .into_iter()
+        .flatten()
+    {
+        notification::validate_template(template)?;
+    }
+    quiet_hours::validate(&settings.quiet_hours)?;
+    if let Some(path) = &settings.database_path {
+        let path = Path::new(path);
+        if !path.is_absolute() {
+            bail!("数据库路径必须是绝对路径: {}", path.display());
+        }
+        if path.is_dir() {
+            bail!("数据库路径是一个目录: {}", path.display());
+        }
+        if !path.parent().is_some_and(Path::is_dir) {
+            bail!("数据库所在的目录不存在: {}", path.display());
+        }
+    }
+    if settings.export_columns.is_empty() {
+        bail!("导出 CSV 至少需要选择一列");
+    }
+    Ok(())
+}
+
+/// 启动时打开的数据库文件：设置中指定的位置，没有指定时为应用数据目录下的 bookmarks.db
+pub fn database_path(app_dir: &Path) -> PathBuf {
+    read_database_location(app_dir)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| app_dir.join(DEFAULT_DATABASE_FILE))
+}
+
+pub fn default_database_path(app_dir: &Path) -> PathBuf {
+    app_dir.join(DEFAULT_DATABASE_FILE)
+}
+
+// 当前使用的自定义数据库位置，没有指定时返回 None
+fn read_database_location(app_dir: &Path) -> Option<String> {
+    read_location(&app_dir.join(DATABASE_LOCATION_FILE))
+}
+
+/// 设置界面中显示的数据库位置：有待生效的更换时为更换后的位置
+pub fn configured_database_location(app_dir: &Path) -> Option<String> {
+    let pending = app_dir.join(PENDING_LOCATION_FILE);
+    if pending.exists() {
+        read_location(&pending)
+    } else {
+        read_database_location(app_dir)
+    }
+}
+
+/// 记录下次启动时要更换到的数据库位置，None 为默认位置。和当前位置相同时取消更换
+pub fn set_database_location(app_dir: &Path, path: Option<&str>) -> Result<()> {
+    let target = path.map(PathBuf::from).unwrap_or_else(|| default_database_path(app_dir));
+    let pending = app_dir.join(PENDING_LOCATION_FILE);
+    if target == database_path(app_dir) {
+        if pending.exists() {
+            fs::remove_file(pending)?;
+        }
+    } else {
+        fs::write(pending, path.unwrap_or_default())?;
+    }
+    Ok(())
+}
+
+/// 启动时打开数据库，返回数据库和需要提示用户的问题。
+///
+/// 先完成待生效的位置更换：新位置还没有数据库文件时把当前的数据库复制过去，复制成功后才改用新位置。
+/// 自定义位置的数据库打不开时（例如所在的磁盘没有挂载）改用默认位置，不修改设置
+pub fn open_database(app_dir: &Path) -> Result<(Database, Option<String>)> {
+    let mut warning = apply_pending_location(app_dir)
+        .err()
+        .map(|e| format!("更换数据库位置失败，继续使用原来的数据库：{}", e));
+
+    let path = database_path(app_dir);
+    let default = default_database_path(app_dir);
+    match Database::new(&path.to_string_lossy()) {
+        Ok(db) => Ok((db, warning)),
+        Err(e) if path != default => {
+            warning = Some(format!("无法打开数据库 {}：{}，已改用默认位置的数据库", path.display(), e));
+            Ok((Database::new(&default.to_string_lossy())?, warning))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn apply_pending_location(app_dir: &Path) -> Result<()> {
+    let pending = app_dir.join(PENDING_LOCATION_FILE);
+    if !pending.exists() {
+        return Ok(());
+    }
+    let target = read_location(&pending);
+    // 不论成功与否只尝试一次，失败时下次启动仍使用原来的位置
+    fs::remove_file(&pending)?;
+
+    let target_path = target.as_deref().map(PathBuf::from).unwrap_or_else(|| default_database_path(app_dir));
+    if !target_path.exists() {
+        let current = Database::new(&database_path(app_dir).to_string_lossy())?;
+        if let Err(e) = current.copy_to(&target_path.to_string_lossy()) {
+            // 不留下复制了一半的文件，否则下次会被当成已有的数据库
+            let _ = fs::remove_file(&target_path);
+            return Err(e);
+        }
+    }
+    write_database_location(app_dir, target.as_deref())
+}
+
+fn read_location(file: &Path) -> Option<String> {
+    fs::read_to_string(file)
+        .ok()
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+}
+
+// 保存数据库位置，None 时恢复默认位置
+fn write_database_location(app_dir: &Path, path: Option<&str>) -> Result<()> {
+    let file = app_dir.join(DATABASE_LOCATION_FILE);
+    match path {
+        Some(path) => fs::write(file, path)?,
+        None if file.exists() => fs::remove_file(file)?,
+        None => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreateBookmarkInput, QuietHours, QuietWindow};
+
+    // 每个测试独占的临时应用数据目录，结束时删除
+    struct AppDir(PathBuf);
+
+    impl AppDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("settings-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            AppDir(dir)
+        }
+
+        fn file(&self, name: &str) -> String {
+            self.0.join(name).to_string_lossy().into_owned()
+        }
+    }
+
+    impl Drop for AppDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn add_bookmark(db: &Database, title: &str) {
+        db.create_bookmark(CreateBookmarkInput {
+            title: title.into(),
+            url: format!("https://{}.example.com/", title),
+            folder_id: None,
+            tags: vec![],
+            icon_url: None,
+            notes: None,
+            keyword: None,
+            reminders: vec![],
+            exclude_from_digest: false,
+        })
+        .unwrap();
+    }
+
+    fn titles(db: &Database) -> Vec<String> {
+        db.get_all_bookmarks().unwrap().into_iter().map(|b| b.title).collect()
+    }
+
+    #[test]
+    fn validate_cases() {
+        let dir = AppDir::new("validate");
+        assert!(validate(&Settings::default()).is_ok());
+        let custom = Settings { database_path: Some(dir.file("custom.db")), ..Settings::default() };
+        assert!(validate(&custom).is_ok());
+
+        let quiet = |start: &str, end: &str| QuietHours {
+            windows: vec![QuietWindow { start: start.into(), end: end.into() }],
+            ..QuietHours::default()
+        };
+        let cases = [
+            (Settings { poll_interval_minutes: 0, ..Settings::default() }, "提醒检查间隔"),
+            (Settings { poll_interval_minutes: 61, ..Settings::default() }, "提醒检查间隔"),
+            (Settings { default_title_template: Some("{nope}".into()), ..Settings::default() }, "未知的占位符"),
+            (Settings { quiet_hours: quiet("25:00", "07:00"), ..Settings::default() }, "免打扰时间格式"),
+            (Settings { quiet_hours: quiet("22:00", "22:00"), ..Settings::default() }, "不能相同"),
+            (
+                Settings {
+                    quiet_hours: QuietHours { paused_until: Some("明天".into()), ..QuietHours::default() },
+                    ..Settings::default()
+                },
+                "暂停时间格式",
+            ),
+            (Settings { database_path: Some("bookmarks.db".into()), ..Settings::default() }, "绝对路径"),
+            (Settings { database_path: Some(dir.file("")), ..Settings::default() }, "是一个目录"),
+            (Settings { database_path: Some(dir.file("missing/custom.db")), ..Settings::default() }, "目录不存在"),
+            (Settings { export_columns: vec![], ..Settings::default() }, "至少需要选择一列"),
+        ];
+        for (settings, expected) in cases {
+            let error = validate(&settings).unwrap_err().to_string();
+            assert!(error.contains(expected), "{}", error);
+        }
+    }
+
+    #[test]
+    fn location_change_waits_for_restart() {
+        let dir = AppDir::new("pending");
+        let custom = dir.file("custom.db");
+        assert_eq!(database_path(&dir.0), default_database_path(&dir.0));
+        assert_eq!(configured_database_location(&dir.0), None);
+
+        set_database_location(&dir.0, Some(&custom)).unwrap();
+        assert_eq!(configured_database_location(&dir.0), Some(custom.clone()));
+        assert_eq!(database_path(&dir.0), default_database_path(&dir.0));
+
+        // 改回当前位置时取消更换
+        set_database_location(&dir.0, None).unwrap();
+        assert!(!dir.0.join(PENDING_LOCATION_FILE).exists());
+        assert_eq!(configured_database_location(&dir.0), None);
+    }
+
+    #[test]
+    fn pending_location_copies_database() {
+        let dir = AppDir::new("copy");
+        let custom = dir.file("custom.db");
+        let (db, warning) = open_database(&dir.0).unwrap();
+        assert_eq!(warning, None);
+        add_bookmark(&db, "rust");
+        drop(db);
+
+        set_database_location(&dir.0, Some(&custom)).unwrap();
+        let (db, warning) = open_database(&dir.0).unwrap();
+        assert_eq!(warning, None);
+        assert_eq!(database_path(&dir.0), PathBuf::from(&custom));
+        assert!(!dir.0.join(PENDING_LOCATION_FILE).exists());
+        assert_eq!(titles(&db), ["rust"]);
+        add_bookmark(&db, "tokio");
+        drop(db);
+
+        // 恢复默认位置：那里已经有数据库，直接使用不覆盖
+        set_database_location(&dir.0, None).unwrap();
+        let (db, warning) = open_database(&dir.0).unwrap();
+        assert_eq!(warning, None);
+        assert_eq!(database_path(&dir.0), default_database_path(&dir.0));
+        assert!(!dir.0.join(DATABASE_LOCATION_FILE).exists());
+        assert_eq!(titles(&db), ["rust"]);
+    }
+
+    #[test]
+    fn failed_copy_keeps_current_location() {
+        let dir = AppDir::new("copy-failed");
+        let (db, _) = open_database(&dir.0).unwrap();
+        add_bookmark(&db, "rust");
+        drop(db);
+
+        let target = dir.file("missing/custom.db");
+        set_database_location(&dir.0, Some(&target)).unwrap();
+        let (db, warning) = open_database(&dir.0).unwrap();
+        assert!(warning.unwrap().contains("更换数据库位置失败"));
+        assert_eq!(database_path(&dir.0), default_database_path(&dir.0));
+        assert!(!dir.0.join(PENDING_LOCATION_FILE).exists());
+        assert!(!Path::new(&target).exists());
+        assert_eq!(titles(&db), ["rust"]);
+    }
+
+    #[test]
+    fn unavailable_location_falls_back_to_default() {
+        let dir = AppDir::new("fallback");
+        let (db, _) = open_database(&dir.0).unwrap();
+        add_bookmark(&db, "rust");
+        drop(db);
+
+        let unmounted = dir.file("unmounted/custom.db");
+        write_database_location(&dir.0, Some(&unmounted)).unwrap();
+        let (db, warning) = open_database(&dir.0).unwrap();
+        assert!(warning.unwrap().contains("已改用默认位置"));
+        assert_eq!(titles(&db), ["rust"]);
+        // 设置不变，磁盘恢复后仍使用自定义位置
+        assert_eq!(configured_database_location(&dir.0), Some(unmounted));
+    }
+}
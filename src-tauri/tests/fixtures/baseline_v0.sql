@@ -0,0 +1,31 @@
+-- 加入版本化迁移之前的数据库：表结构与当时 Database::init_tables 建的完全一致，没有设置 user_version
+CREATE TABLE IF NOT EXISTS bookmarks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                title TEXT NOT NULL,
+                url TEXT NOT NULL,
+                category TEXT,
+                tags TEXT,
+                icon_url TEXT,
+                notes TEXT,
+                reminder TEXT,
+                visit_count INTEGER DEFAULT 0,
+                last_visited TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+CREATE INDEX IF NOT EXISTS idx_bookmarks_category ON bookmarks(category);
+CREATE INDEX IF NOT EXISTS idx_bookmarks_created_at ON bookmarks(created_at);
+
+INSERT INTO bookmarks (title, url, category, tags, icon_url, notes, reminder, visit_count, last_visited, created_at, updated_at)
+VALUES ('Rust 文档', 'https://Doc.Rust-Lang.org/?utm_source=feed', ' 开发 ', '["rust","docs"]', NULL, '官方文档',
+        '{"enabled":true,"frequency":{"type":"daily"},"time":"09:00","days":[],"last_reminded":null,"next_reminder":"2024-02-02T01:00:00+00:00"}',
+        3, '2024-02-01T10:00:00+00:00', '2024-01-05T08:00:00+00:00', '2024-01-06T08:00:00+00:00');
+
+INSERT INTO bookmarks (title, url, category, tags, icon_url, notes, reminder, visit_count, last_visited, created_at, updated_at)
+VALUES ('MDN', 'https://developer.mozilla.org/zh-CN/', '开发', '["docs"]', 'https://developer.mozilla.org/favicon.ico', NULL,
+        '{"enabled":false,"frequency":{"type":"weekly"},"time":"08:30","days":[1,5],"last_reminded":null,"next_reminder":null}',
+        0, NULL, '2024-01-07T08:00:00+00:00', '2024-01-07T08:00:00+00:00');
+
+INSERT INTO bookmarks (title, url, category, tags, icon_url, notes, reminder, created_at, updated_at)
+VALUES ('Hacker News', 'https://news.ycombinator.com/', NULL, '[]', NULL, NULL, 'not json',
+        '2024-01-08T08:00:00+00:00', '2024-01-08T08:00:00+00:00');
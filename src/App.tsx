@@ -1,62 +1,125 @@
 import { useState, useEffect } from "react";
 import { bookmarkApi } from "./api";
-import type { Bookmark, CreateBookmarkInput, Reminder } from "./types";
+import type { Bookmark, CreateBookmarkInput, Folder, ImportProgress, Reminder, ReminderDigest, ReminderNotification, ReminderStats } from "./types";
 import { listen } from "@tauri-apps/api/event";
 import { open } from "@tauri-apps/plugin-dialog";
 import { Toast } from "./Toast";
+import { QuietHoursDialog } from "./QuietHoursDialog";
+import { SettingsDialog } from "./SettingsDialog";
 import "./App.css";
 import "./DarkTheme.css";
 import "./Shortcuts.css";
 import "./FolderView.css";
 
+// 新建提醒默认使用本机时区
+const currentTimezone = Intl.DateTimeFormat().resolvedOptions().timeZone;
+
+// 提醒的简短描述，例如「09:00 • 每日提醒」
+const describeReminder = (reminder: Reminder) => {
+  const freq = reminder.frequency;
+  const label =
+    freq.type === "daily" ? "每日提醒"
+    : freq.type === "weekly" ? "每周提醒"
+    : freq.type === "custom" ? `每 ${freq.interval_days} 天`
+    : freq.type === "once" ? "一次性提醒"
+    : "自定义规则";
+  const zone = reminder.timezone && reminder.timezone !== currentTimezone ? ` (${reminder.timezone})` : "";
+  return `${reminder.time}${zone} • ${label}`;
+};
+
 function App() {
   const [bookmarks, setBookmarks] = useState<Bookmark[]>([]);
+  const [folders, setFolders] = useState<Folder[]>([]);
   const [searchQuery, setSearchQuery] = useState("");
   const [showAddForm, setShowAddForm] = useState(false);
   const [editingId, setEditingId] = useState<number | null>(null);
   const [showReminders, setShowReminders] = useState(false);
+  const [reminderStats, setReminderStats] = useState<Record<number, ReminderStats>>({});
   const [formData, setFormData] = useState<CreateBookmarkInput>({
     title: "",
     url: "",
-    category: "",
+    folder_id: undefined,
     tags: [],
     notes: "",
-    reminder: undefined,
+    reminders: [],
+    exclude_from_digest: false,
   });
-  const [reminderEnabled, setReminderEnabled] = useState(false);
-  const [reminderFrequency, setReminderFrequency] = useState<"daily" | "weekly" | "custom" | "once">("daily");
+  // 提醒编辑器：editingReminder 为正在修改的提醒在 formData.reminders 中的位置，null 为新提醒
+  const [reminderEditorOpen, setReminderEditorOpen] = useState(false);
+  const [editingReminder, setEditingReminder] = useState<number | null>(null);
+  const [reminderEnabled, setReminderEnabled] = useState(true);
+  const [reminderFrequency, setReminderFrequency] = useState<"daily" | "weekly" | "custom" | "once" | "rrule">("daily");
   const [reminderTime, setReminderTime] = useState("09:00");
   const [reminderDays, setReminderDays] = useState<number[]>([]);
   const [reminderInterval, setReminderInterval] = useState(1);
+  const [reminderRule, setReminderRule] = useState("");
+  const [reminderTimezone, setReminderTimezone] = useState(currentTimezone);
+  const [reminderTitleTemplate, setReminderTitleTemplate] = useState("");
+  const [reminderMessageTemplate, setReminderMessageTemplate] = useState("");
+  const [reminderPreview, setReminderPreview] = useState<{ times: string[]; error?: string } | null>(null);
   const [toast, setToast] = useState<{ message: string; type: 'success' | 'error' | 'info' } | null>(null);
   const [theme, setTheme] = useState<'light' | 'dark'>(() => {
     const saved = localStorage.getItem('theme');
     return (saved as 'light' | 'dark') || 'light';
   });
   const [showHelp, setShowHelp] = useState(false);
+  const [showQuietHours, setShowQuietHours] = useState(false);
+  const [showSettings, setShowSettings] = useState(false);
   const [viewMode, setViewMode] = useState<'grid' | 'folder'>('grid');
+  const [importProgress, setImportProgress] = useState<ImportProgress | null>(null);
 
   useEffect(() => {
     loadBookmarks();
 
     // Listen for reminder events from backend
-    const unlisten = listen<Bookmark>("reminder-triggered", (event) => {
-      const bookmark = event.payload;
-      if (confirm(`提醒：该去 ${bookmark.title} 签到了！\n\n是否标记为已完成？`)) {
-        bookmarkApi.markReminderCompleted(bookmark.id!);
+    const unlisten = listen<ReminderNotification>("reminder-triggered", (event) => {
+      const { reminder, body, actions } = event.payload;
+      // 通知上有按钮时由用户在通知上操作
+      if (actions) return;
+      if (confirm(`${body}\n\n是否标记为已完成？`)) {
+        bookmarkApi.markReminderCompleted(reminder.id!);
+      } else {
+        bookmarkApi.dismissReminder(reminder.id!);
+      }
+    });
+    // 多个提醒合并成的摘要，询问一次，对所有提醒生效
+    const unlistenDigest = listen<ReminderDigest>("reminder-digest", (event) => {
+      const { items, body, actions } = event.payload;
+      if (actions) return;
+      const completed = confirm(`${body}\n\n是否全部标记为已完成？`);
+      for (const { reminder } of items) {
+        if (completed) {
+          bookmarkApi.markReminderCompleted(reminder.id!);
+        } else {
+          bookmarkApi.dismissReminder(reminder.id!);
+        }
       }
     });
+    // 在系统通知上点了按钮
+    const unlistenAction = listen<number>("reminder-action-performed", () => {
+      loadBookmarks();
+    });
+    // 在摘要通知上点了“打开”
+    const unlistenShow = listen("show-reminders", () => {
+      setShowReminders(true);
+    });
 
     return () => {
       unlisten.then((fn) => fn());
+      unlistenDigest.then((fn) => fn());
+      unlistenAction.then((fn) => fn());
+      unlistenShow.then((fn) => fn());
     };
   }, []);
 
   const loadBookmarks = async () => {
     try {
+      setFolders(await bookmarkApi.listFolders());
       if (showReminders) {
         const data = await bookmarkApi.getBookmarksWithReminders();
         setBookmarks(data);
+        const stats = await bookmarkApi.getReminderStats();
+        setReminderStats(Object.fromEntries(stats.map((s) => [s.reminder_id, s])));
       } else {
         const data = await bookmarkApi.getAllBookmarks();
         setBookmarks(data);
@@ -73,7 +136,7 @@ function App() {
     }
     try {
       const results = await bookmarkApi.searchBookmarks(searchQuery);
-      setBookmarks(results);
+      setBookmarks(results.map((result) => result.bookmark));
       setShowReminders(false);
     } catch (error) {
       setToast({ message: "搜索失败：" + String(error), type: "error" });
@@ -89,10 +152,11 @@ function App() {
           id: editingId,
           title: formData.title,
           url: formData.url,
-          category: formData.category || undefined,
+          folder_id: formData.folder_id,
           tags: formData.tags.filter(t => t.trim()),
           notes: formData.notes || undefined,
-          reminder: reminderEnabled ? buildReminder() : undefined,
+          reminders: collectReminders(),
+          exclude_from_digest: formData.exclude_from_digest,
         };
 
         await bookmarkApi.updateBookmark(updateData);
@@ -101,10 +165,11 @@ function App() {
         const bookmarkData: CreateBookmarkInput = {
           title: formData.title,
           url: formData.url,
-          category: formData.category || undefined,
+          folder_id: formData.folder_id,
           tags: formData.tags.filter(t => t.trim()),
           notes: formData.notes || undefined,
-          reminder: reminderEnabled ? buildReminder() : undefined,
+          reminders: collectReminders(),
+          exclude_from_digest: formData.exclude_from_digest,
         };
 
         await bookmarkApi.createBookmark(bookmarkData);
@@ -126,31 +191,82 @@ function App() {
       frequency = { type: "weekly" as const };
     } else if (reminderFrequency === "custom") {
       frequency = { type: "custom" as const, interval_days: reminderInterval };
+    } else if (reminderFrequency === "rrule") {
+      frequency = { type: "rrule" as const, rule: reminderRule };
     } else {
       frequency = { type: "once" as const };
     }
 
     return {
-      enabled: true,
+      id: editingReminder !== null ? formData.reminders[editingReminder].id : undefined,
+      enabled: reminderEnabled,
       frequency,
       time: reminderTime,
       days: reminderDays,
+      timezone: reminderTimezone.trim() || undefined,
+      title_template: reminderTitleTemplate.trim() || undefined,
+      message_template: reminderMessageTemplate.trim() || undefined,
     };
   };
 
+  // 编辑器中还没确定的提醒也一起保存：修改正在编辑的那个，或者追加
+  const collectReminders = (): Reminder[] => {
+    if (!reminderEditorOpen) return formData.reminders;
+    const reminder = buildReminder();
+    return editingReminder !== null
+      ? formData.reminders.map((r, idx) => (idx === editingReminder ? reminder : r))
+      : [...formData.reminders, reminder];
+  };
+
+  const handleSaveReminder = () => {
+    setFormData({ ...formData, reminders: collectReminders() });
+    closeReminderEditor();
+  };
+
+  const handleRemoveReminder = (index: number) => {
+    setFormData({ ...formData, reminders: formData.reminders.filter((_, idx) => idx !== index) });
+    closeReminderEditor();
+  };
+
+  const openReminderEditor = (index: number | null) => {
+    const reminder = index !== null ? formData.reminders[index] : undefined;
+    setReminderEnabled(reminder?.enabled ?? true);
+    setReminderTime(reminder?.time ?? "09:00");
+    setReminderDays(reminder?.days ?? []);
+    setReminderTimezone(reminder?.timezone || currentTimezone);
+    setReminderTitleTemplate(reminder?.title_template || "");
+    setReminderMessageTemplate(reminder?.message_template || "");
+    setReminderInterval(1);
+    setReminderRule("");
+
+    const freq = reminder?.frequency ?? { type: "daily" as const };
+    setReminderFrequency(freq.type);
+    if (freq.type === "custom") {
+      setReminderInterval(freq.interval_days);
+    } else if (freq.type === "rrule") {
+      setReminderRule(freq.rule);
+    }
+
+    setEditingReminder(index);
+    setReminderEditorOpen(true);
+  };
+
+  const closeReminderEditor = () => {
+    setReminderEditorOpen(false);
+    setEditingReminder(null);
+  };
+
   const resetForm = () => {
     setFormData({
       title: "",
       url: "",
-      category: "",
+      folder_id: undefined,
       tags: [],
       notes: "",
+      reminders: [],
+      exclude_from_digest: false,
     });
-    setReminderEnabled(false);
-    setReminderFrequency("daily");
-    setReminderTime("09:00");
-    setReminderDays([]);
-    setReminderInterval(1);
+    closeReminderEditor();
     setShowAddForm(false);
     setEditingId(null);
   };
@@ -159,28 +275,13 @@ function App() {
     setFormData({
       title: bookmark.title,
       url: bookmark.url,
-      category: bookmark.category || "",
+      folder_id: bookmark.folder_id,
       tags: bookmark.tags,
       notes: bookmark.notes || "",
+      reminders: bookmark.reminders,
+      exclude_from_digest: bookmark.exclude_from_digest,
     });
-
-    if (bookmark.reminder) {
-      setReminderEnabled(bookmark.reminder.enabled);
-      setReminderTime(bookmark.reminder.time);
-      setReminderDays(bookmark.reminder.days);
-
-      const freq = bookmark.reminder.frequency;
-      if (freq.type === "daily") {
-        setReminderFrequency("daily");
-      } else if (freq.type === "weekly") {
-        setReminderFrequency("weekly");
-      } else if (freq.type === "custom") {
-        setReminderFrequency("custom");
-        setReminderInterval(freq.interval_days);
-      } else {
-        setReminderFrequency("once");
-      }
-    }
+    closeReminderEditor();
 
     setEditingId(bookmark.id!);
     setShowAddForm(true);
@@ -224,9 +325,9 @@ function App() {
     { value: 6, label: "六" },
   ];
 
-  const handleMarkCompleted = async (bookmarkId: number) => {
+  const handleMarkCompleted = async (reminderId: number) => {
     try {
-      await bookmarkApi.markReminderCompleted(bookmarkId);
+      await bookmarkApi.markReminderCompleted(reminderId);
       setToast({ message: "已标记为完成", type: "success" });
       loadBookmarks();
     } catch (error) {
@@ -241,12 +342,43 @@ function App() {
         filters: [{
           name: 'HTML',
           extensions: ['html', 'htm']
+        }, {
+          name: 'Firefox (places.sqlite)',
+          extensions: ['sqlite']
+        }, {
+          name: 'JSON（完整备份、Pinboard）',
+          extensions: ['json']
+        }, {
+          name: 'CSV（Pocket、Raindrop）',
+          extensions: ['csv']
+        }, {
+          name: 'Chrome / Edge (Bookmarks)',
+          extensions: ['*']
         }]
       });
 
       if (selected && typeof selected === 'string') {
-        const count = await bookmarkApi.importBookmarks(selected);
-        setToast({ message: `成功导入 ${count} 个书签！`, type: "success" });
+        const unlisten = await listen<ImportProgress>("import-progress", (event) => {
+          setImportProgress(event.payload);
+        });
+        try {
+          // 格式由后端根据文件内容判断
+          const report = await bookmarkApi.importBookmarks(selected);
+          const failed = report.failed.length > 0 ? `，失败 ${report.failed.length} 个` : "";
+          // 导入按批次提交，中途停止时之前的批次已经写入
+          const stopped = report.error
+            ? `导入中途出错（${report.error}），已保留出错前导入的书签：`
+            : report.cancelled
+              ? "导入已取消，已保留取消前导入的书签："
+              : "";
+          setToast({
+            message: `${stopped}成功导入 ${report.created} 个书签，跳过 ${report.skipped} 个已存在的书签${failed}！`,
+            type: report.error ? "error" : report.failed.length > 0 || report.cancelled ? "info" : "success",
+          });
+        } finally {
+          unlisten();
+          setImportProgress(null);
+        }
         loadBookmarks();
       }
     } catch (error) {
@@ -254,19 +386,37 @@ function App() {
     }
   };
 
+  const importPercent = (progress: ImportProgress) => {
+    if (progress.total) {
+      return Math.round((progress.processed / progress.total) * 100);
+    }
+    if (progress.total_bytes > 0) {
+      return Math.round((progress.bytes_read / progress.total_bytes) * 100);
+    }
+    return 0;
+  };
+
   const handleExportBookmarks = async () => {
     try {
       const { save } = await import('@tauri-apps/plugin-dialog');
+      const { export_format } = await bookmarkApi.getSettings();
       const filePath = await save({
         filters: [{
           name: 'HTML',
           extensions: ['html']
+        }, {
+          name: 'JSON（完整备份）',
+          extensions: ['json']
+        }, {
+          name: 'CSV',
+          extensions: ['csv']
         }],
-        defaultPath: 'bookmarks.html'
+        defaultPath: `bookmarks.${export_format}`
       });
 
       if (filePath) {
-        const count = await bookmarkApi.exportBookmarks(filePath);
+        const format = filePath.endsWith('.json') ? 'json' : filePath.endsWith('.csv') ? 'csv' : 'html';
+        const count = await bookmarkApi.exportBookmarks(filePath, format);
         setToast({ message: `成功导出 ${count} 个书签！`, type: "success" });
       }
     } catch (error) {
@@ -278,6 +428,32 @@ function App() {
     loadBookmarks();
   }, [showReminders]);
 
+  // 提醒设置变化时预览接下来几次提醒，规则有误时显示错误
+  useEffect(() => {
+    if (!reminderEditorOpen) {
+      setReminderPreview(null);
+      return;
+    }
+    let cancelled = false;
+    bookmarkApi
+      .previewReminderSchedule(buildReminder(), 3)
+      .then((times) => !cancelled && setReminderPreview({ times }))
+      .catch((error) => !cancelled && setReminderPreview({ times: [], error: String(error) }));
+    return () => {
+      cancelled = true;
+    };
+  }, [
+    reminderEditorOpen,
+    reminderFrequency,
+    reminderTime,
+    reminderDays,
+    reminderInterval,
+    reminderRule,
+    reminderTimezone,
+    reminderTitleTemplate,
+    reminderMessageTemplate,
+  ]);
+
   useEffect(() => {
     document.documentElement.setAttribute('data-theme', theme);
     localStorage.setItem('theme', theme);
@@ -351,7 +527,7 @@ function App() {
   const groupedBookmarks = () => {
     const groups: { [key: string]: Bookmark[] } = {};
     bookmarks.forEach(bookmark => {
-      const category = bookmark.category || '未分类';
+      const category = bookmark.folder_path || '未分类';
       if (!groups[category]) {
         groups[category] = [];
       }
@@ -372,13 +548,13 @@ function App() {
           }}
         />
         <h3>{bookmark.title}</h3>
-        {bookmark.reminder?.enabled && (
+        {bookmark.reminders.some((r) => r.enabled) && (
           <span className="reminder-badge" title="已设置提醒">🔔</span>
         )}
       </div>
       <p className="bookmark-url">🔗 {bookmark.url}</p>
-      {bookmark.category && (
-        <span className="category-tag">📁 {bookmark.category}</span>
+      {bookmark.folder_path && (
+        <span className="category-tag">📁 {bookmark.folder_path}</span>
       )}
       {bookmark.tags.length > 0 && (
         <div className="tags">
@@ -392,16 +568,27 @@ function App() {
       {bookmark.notes && (
         <p className="notes">💭 {bookmark.notes}</p>
       )}
-      {bookmark.reminder?.enabled && (
+      {bookmark.reminders.some((r) => r.enabled) && (
         <div className="reminder-info">
-          <small>
-            ⏰ {bookmark.reminder.time} •{" "}
-            {bookmark.reminder.frequency.type === "daily" && "每日提醒"}
-            {bookmark.reminder.frequency.type === "weekly" && "每周提醒"}
-            {bookmark.reminder.frequency.type === "custom" &&
-              `每 ${bookmark.reminder.frequency.interval_days} 天`}
-            {bookmark.reminder.frequency.type === "once" && "一次性提醒"}
-          </small>
+          {bookmark.reminders.filter((r) => r.enabled).map((reminder) => (
+            <div key={reminder.id} className="reminder-row">
+              <small>
+                ⏰ {describeReminder(reminder)}
+                {showReminders && (reminderStats[reminder.id!]?.current_streak ?? 0) > 0 && (
+                  <> • 🔥 连续完成 {reminderStats[reminder.id!].current_streak} 次</>
+                )}
+              </small>
+              {showReminders && (
+                <button
+                  className="btn-success"
+                  onClick={() => handleMarkCompleted(reminder.id!)}
+                  title="标记为已完成"
+                >
+                  ✅ 完成
+                </button>
+              )}
+            </div>
+          ))}
         </div>
       )}
       <div className="bookmark-stats">
@@ -421,15 +608,6 @@ function App() {
         <button onClick={() => handleOpenUrl(bookmark.url, bookmark.id!)} title="打开网站">
           🚀 打开
         </button>
-        {showReminders && (
-          <button
-            className="btn-success"
-            onClick={() => handleMarkCompleted(bookmark.id!)}
-            title="标记为已完成"
-          >
-            ✅ 完成
-          </button>
-        )}
         <button onClick={() => handleEditBookmark(bookmark)} title="编辑书签">
           ✏️ 编辑
         </button>
@@ -449,6 +627,13 @@ function App() {
           onClose={() => setToast(null)}
         />
       )}
+      {importProgress && (
+        <div className="import-progress">
+          <span>正在导入：已处理 {importProgress.processed} 个书签</span>
+          <progress value={importPercent(importProgress)} max={100} />
+          <button onClick={() => bookmarkApi.cancelImport()}>取消</button>
+        </div>
+      )}
       <header className="header">
         <div className="header-top">
           <h1>📚 书签管理器</h1>
@@ -456,6 +641,12 @@ function App() {
             <button className="theme-toggle" onClick={toggleTheme} title="切换主题 (Ctrl+T)">
               {theme === 'light' ? '🌙' : '☀️'}
             </button>
+            <button className="help-button" onClick={() => setShowQuietHours(true)} title="免打扰与提醒摘要">
+              🔕
+            </button>
+            <button className="help-button" onClick={() => setShowSettings(true)} title="设置">
+              ⚙️
+            </button>
             <button className="help-button" onClick={() => setShowHelp(true)} title="快捷键帮助 (?)">
               ❓
             </button>
@@ -513,14 +704,22 @@ function App() {
               }
               required
             />
-            <input
-              type="text"
-              placeholder="📁 分类（可选）"
-              value={formData.category}
+            <select
+              value={formData.folder_id ?? ""}
               onChange={(e) =>
-                setFormData({ ...formData, category: e.target.value })
+                setFormData({
+                  ...formData,
+                  folder_id: e.target.value ? Number(e.target.value) : undefined,
+                })
               }
-            />
+            >
+              <option value="">📁 文件夹（可选）</option>
+              {folders.map((folder) => (
+                <option key={folder.id} value={folder.id}>
+                  {folder.path}
+                </option>
+              ))}
+            </select>
             <input
               type="text"
               placeholder="🏷️ 标签（用逗号分隔，可选）"
@@ -541,26 +740,62 @@ function App() {
             />
 
             <div className="reminder-section">
-              <h3>
+              <h3>🔔 提醒</h3>
+
+              {formData.reminders.length > 0 && (
+                <ul className="reminder-list">
+                  {formData.reminders.map((reminder, idx) => (
+                    <li key={reminder.id ?? `new-${idx}`}>
+                      <span>
+                        ⏰ {describeReminder(reminder)}
+                        {!reminder.enabled && "（已停用）"}
+                      </span>
+                      <button type="button" onClick={() => openReminderEditor(idx)} title="修改提醒">
+                        ✏️
+                      </button>
+                      <button type="button" onClick={() => handleRemoveReminder(idx)} title="删除提醒">
+                        🗑️
+                      </button>
+                    </li>
+                  ))}
+                </ul>
+              )}
+
+              {formData.reminders.length > 0 && (
                 <label>
                   <input
                     type="checkbox"
-                    checked={reminderEnabled}
-                    onChange={(e) => setReminderEnabled(e.target.checked)}
+                    checked={formData.exclude_from_digest ?? false}
+                    onChange={(e) => setFormData({ ...formData, exclude_from_digest: e.target.checked })}
                   />
-                  🔔 启用提醒
+                  不合并到提醒摘要，每次单独通知
                 </label>
-              </h3>
+              )}
+
+              {!reminderEditorOpen && (
+                <button type="button" onClick={() => openReminderEditor(null)}>
+                  ➕ 添加提醒
+                </button>
+              )}
 
-              {reminderEnabled && (
+              {reminderEditorOpen && (
                 <div className="reminder-config">
+                  <label>
+                    <input
+                      type="checkbox"
+                      checked={reminderEnabled}
+                      onChange={(e) => setReminderEnabled(e.target.checked)}
+                    />
+                    启用此提醒
+                  </label>
+
                   <div className="form-group">
                     <label>⏰ 提醒频率</label>
                     <select
                       value={reminderFrequency}
                       onChange={(e) =>
                         setReminderFrequency(
-                          e.target.value as "daily" | "weekly" | "custom" | "once"
+                          e.target.value as "daily" | "weekly" | "custom" | "once" | "rrule"
                         )
                       }
                     >
@@ -568,6 +803,7 @@ function App() {
                       <option value="weekly">每周提醒</option>
                       <option value="custom">自定义间隔</option>
                       <option value="once">一次性提醒</option>
+                      <option value="rrule">高级规则（RRULE）</option>
                     </select>
                   </div>
 
@@ -580,6 +816,16 @@ function App() {
                     />
                   </div>
 
+                  <div className="form-group">
+                    <label>🌐 时区</label>
+                    <input
+                      type="text"
+                      value={reminderTimezone}
+                      onChange={(e) => setReminderTimezone(e.target.value)}
+                      placeholder="例如：Asia/Shanghai"
+                    />
+                  </div>
+
                   {reminderFrequency === "weekly" && (
                     <div className="form-group">
                       <label>📅 选择星期</label>
@@ -616,6 +862,66 @@ function App() {
                       />
                     </div>
                   )}
+
+                  {reminderFrequency === "rrule" && (
+                    <div className="form-group">
+                      <label>🔁 重复规则</label>
+                      <input
+                        type="text"
+                        value={reminderRule}
+                        onChange={(e) => setReminderRule(e.target.value)}
+                        placeholder="例如：FREQ=MONTHLY;BYDAY=1MO 表示每月第一个周一"
+                      />
+                    </div>
+                  )}
+
+                  <div className="form-group">
+                    <label>💬 通知内容（可选）</label>
+                    <input
+                      type="text"
+                      value={reminderTitleTemplate}
+                      onChange={(e) => setReminderTitleTemplate(e.target.value)}
+                      placeholder="标题，默认：签到提醒"
+                    />
+                    <input
+                      type="text"
+                      value={reminderMessageTemplate}
+                      onChange={(e) => setReminderMessageTemplate(e.target.value)}
+                      placeholder="内容，默认：该去 {title} 签到了！可用 {title} {url} {folder} {streak} {time}"
+                    />
+                  </div>
+
+                  {reminderPreview && (
+                    <div className="reminder-preview">
+                      {reminderPreview.error ? (
+                        <small className="error">⚠️ {reminderPreview.error}</small>
+                      ) : (
+                        <small>
+                          接下来：
+                          {reminderPreview.times.length > 0
+                            ? reminderPreview.times
+                                .map((t) => new Date(t).toLocaleString("zh-CN", {
+                                  month: "short",
+                                  day: "numeric",
+                                  weekday: "short",
+                                  hour: "2-digit",
+                                  minute: "2-digit",
+                                }))
+                                .join("、")
+                            : "没有后续提醒"}
+                        </small>
+                      )}
+                    </div>
+                  )}
+
+                  <div className="reminder-editor-actions">
+                    <button type="button" onClick={handleSaveReminder}>
+                      {editingReminder !== null ? "✔️ 确定修改" : "✔️ 添加"}
+                    </button>
+                    <button type="button" onClick={closeReminderEditor}>
+                      取消
+                    </button>
+                  </div>
                 </div>
               )}
             </div>
@@ -656,6 +962,20 @@ function App() {
         )}
       </div>
 
+      {showQuietHours && (
+        <QuietHoursDialog
+          onClose={() => setShowQuietHours(false)}
+          onMessage={(message, type) => setToast({ message, type })}
+        />
+      )}
+
+      {showSettings && (
+        <SettingsDialog
+          onClose={() => setShowSettings(false)}
+          onMessage={(message, type) => setToast({ message, type })}
+        />
+      )}
+
       {showHelp && (
         <div className="modal-overlay" onClick={() => setShowHelp(false)}>
           <div className="modal-content" onClick={(e) => e.stopPropagation()}>
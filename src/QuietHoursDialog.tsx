@@ -0,0 +1,156 @@
+import { useEffect, useState } from 'react';
+import { bookmarkApi } from './api';
+import type { QuietHours, QuietWindow, Settings } from './types';
+import './QuietHoursDialog.css';
+
+export interface QuietHoursDialogProps {
+  onClose: () => void;
+  onMessage: (message: string, type: 'success' | 'error') => void;
+}
+
+// 明天早上 8 点
+const tomorrowMorning = () => {
+  const date = new Date();
+  date.setDate(date.getDate() + 1);
+  date.setHours(8, 0, 0, 0);
+  return date;
+};
+
+export function QuietHoursDialog({ onClose, onMessage }: QuietHoursDialogProps) {
+  const [settings, setSettings] = useState<Settings | null>(null);
+
+  useEffect(() => {
+    bookmarkApi
+      .getSettings()
+      .then(setSettings)
+      .catch((error) => onMessage('加载免打扰设置失败：' + String(error), 'error'));
+  }, []);
+
+  if (!settings) return null;
+
+  const quietHours = settings.quiet_hours;
+  const setQuietHours = (quiet_hours: QuietHours) => setSettings({ ...settings, quiet_hours });
+
+  const updateWindow = (index: number, window: QuietWindow) => {
+    setQuietHours({
+      ...quietHours,
+      windows: quietHours.windows.map((w, idx) => (idx === index ? window : w)),
+    });
+  };
+
+  const handleSave = async () => {
+    try {
+      setSettings(await bookmarkApi.updateSettings(settings));
+      onMessage('设置已保存', 'success');
+      onClose();
+    } catch (error) {
+      onMessage('保存失败：' + String(error), 'error');
+    }
+  };
+
+  const handlePause = async (until?: Date) => {
+    try {
+      // 暂停立即生效，不连带保存对话框中其他未保存的修改
+      const current = await bookmarkApi.getSettings();
+      const updated = await bookmarkApi.updateSettings({
+        ...current,
+        quiet_hours: { ...current.quiet_hours, paused_until: until?.toISOString() },
+      });
+      setQuietHours({ ...quietHours, paused_until: updated.quiet_hours.paused_until });
+      onMessage(until ? '提醒已暂停' : '已恢复提醒', 'success');
+    } catch (error) {
+      onMessage('操作失败：' + String(error), 'error');
+    }
+  };
+
+  const paused = quietHours.paused_until && new Date(quietHours.paused_until) > new Date();
+
+  return (
+    <div className="modal-overlay" onClick={onClose}>
+      <div className="modal-content quiet-hours" onClick={(e) => e.stopPropagation()}>
+        <h2>🔕 免打扰与摘要</h2>
+
+        <div className="quiet-pause">
+          {paused ? (
+            <>
+              <span>
+                ⏸️ 提醒已暂停到 {new Date(quietHours.paused_until!).toLocaleString('zh-CN', {
+                  month: 'short',
+                  day: 'numeric',
+                  hour: '2-digit',
+                  minute: '2-digit',
+                })}
+              </span>
+              <button onClick={() => handlePause(undefined)}>▶️ 恢复提醒</button>
+            </>
+          ) : (
+            <>
+              <button onClick={() => handlePause(new Date(Date.now() + 60 * 60 * 1000))}>暂停 1 小时</button>
+              <button onClick={() => handlePause(tomorrowMorning())}>暂停到明天 8:00</button>
+            </>
+          )}
+        </div>
+
+        <h3>🌙 每天的免打扰时段</h3>
+        {quietHours.windows.map((window, idx) => (
+          <div key={idx} className="quiet-window">
+            <input
+              type="time"
+              value={window.start}
+              onChange={(e) => updateWindow(idx, { ...window, start: e.target.value })}
+            />
+            <span>至</span>
+            <input
+              type="time"
+              value={window.end}
+              onChange={(e) => updateWindow(idx, { ...window, end: e.target.value })}
+            />
+            <button
+              onClick={() =>
+                setQuietHours({ ...quietHours, windows: quietHours.windows.filter((_, i) => i !== idx) })
+              }
+              title="删除时段"
+            >
+              🗑️
+            </button>
+          </div>
+        ))}
+        <button
+          onClick={() =>
+            setQuietHours({ ...quietHours, windows: [...quietHours.windows, { start: '22:00', end: '08:00' }] })
+          }
+        >
+          ➕ 添加时段
+        </button>
+
+        <div className="form-group">
+          <label>免打扰期间到期的提醒</label>
+          <select
+            value={quietHours.policy}
+            onChange={(e) => setQuietHours({ ...quietHours, policy: e.target.value as QuietHours['policy'] })}
+          >
+            <option value="defer">推迟到免打扰结束时提醒</option>
+            <option value="drop">不提醒，等下一次</option>
+          </select>
+        </div>
+
+        <h3>📋 提醒摘要</h3>
+        <label>
+          <input
+            type="checkbox"
+            checked={settings.reminder_digest}
+            onChange={(e) => setSettings({ ...settings, reminder_digest: e.target.checked })}
+          />
+          同时到期的多个提醒合并成一条通知
+        </label>
+
+        <div className="form-actions">
+          <button onClick={handleSave}>💾 保存</button>
+          <button className="modal-close" onClick={onClose}>
+            关闭
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
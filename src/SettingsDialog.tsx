@@ -0,0 +1,173 @@
+import { useEffect, useState } from 'react';
+import { save } from '@tauri-apps/plugin-dialog';
+import { bookmarkApi } from './api';
+import type { CsvColumn, ExportFormat, NotificationLanguage, Settings } from './types';
+import './SettingsDialog.css';
+
+export interface SettingsDialogProps {
+  onClose: () => void;
+  onMessage: (message: string, type: 'success' | 'error') => void;
+}
+
+// CSV 列和显示名称，顺序与导出的列顺序一致
+const CSV_COLUMNS: [CsvColumn, string][] = [
+  ['title', '标题'],
+  ['url', '网址'],
+  ['folder', '文件夹'],
+  ['tags', '标签'],
+  ['notes', '备注'],
+  ['keyword', '关键词'],
+  ['icon_url', '图标'],
+  ['visit_count', '访问次数'],
+  ['last_visited', '最后访问'],
+  ['created_at', '创建时间'],
+  ['updated_at', '修改时间'],
+  ['reminder', '提醒'],
+];
+
+export function SettingsDialog({ onClose, onMessage }: SettingsDialogProps) {
+  const [settings, setSettings] = useState<Settings | null>(null);
+
+  useEffect(() => {
+    bookmarkApi
+      .getSettings()
+      .then(setSettings)
+      .catch((error) => onMessage('加载设置失败：' + String(error), 'error'));
+  }, []);
+
+  if (!settings) return null;
+
+  const update = (changes: Partial<Settings>) => setSettings({ ...settings, ...changes });
+
+  const toggleColumn = (column: CsvColumn, checked: boolean) => {
+    const selected = checked
+      ? [...settings.export_columns, column]
+      : settings.export_columns.filter((c) => c !== column);
+    // 保持列的固定顺序
+    update({ export_columns: CSV_COLUMNS.map(([c]) => c).filter((c) => selected.includes(c)) });
+  };
+
+  const handleChooseDatabase = async () => {
+    const path = await save({
+      filters: [{ name: 'SQLite 数据库', extensions: ['db'] }],
+      defaultPath: settings.database_path ?? 'bookmarks.db',
+    });
+    if (path) update({ database_path: path });
+  };
+
+  const handleSave = async () => {
+    try {
+      await bookmarkApi.updateSettings({
+        ...settings,
+        default_title_template: settings.default_title_template?.trim() || undefined,
+        default_message_template: settings.default_message_template?.trim() || undefined,
+      });
+      onMessage('设置已保存', 'success');
+      onClose();
+    } catch (error) {
+      onMessage('保存失败：' + String(error), 'error');
+    }
+  };
+
+  return (
+    <div className="modal-overlay" onClick={onClose}>
+      <div className="modal-content settings-dialog" onClick={(e) => e.stopPropagation()}>
+        <h2>⚙️ 设置</h2>
+
+        <h3>🔔 通知</h3>
+        <div className="form-group">
+          <label>通知语言</label>
+          <select
+            value={settings.notification_language ?? ''}
+            onChange={(e) =>
+              update({ notification_language: (e.target.value || undefined) as NotificationLanguage | undefined })
+            }
+          >
+            <option value="">跟随系统</option>
+            <option value="chinese">中文</option>
+            <option value="english">English</option>
+          </select>
+        </div>
+        <div className="form-group">
+          <label>默认通知标题和内容（提醒没有单独设置时使用）</label>
+          <input
+            type="text"
+            placeholder="签到提醒"
+            value={settings.default_title_template ?? ''}
+            onChange={(e) => update({ default_title_template: e.target.value })}
+          />
+          <input
+            type="text"
+            placeholder="该去 {title} 签到了！"
+            value={settings.default_message_template ?? ''}
+            onChange={(e) => update({ default_message_template: e.target.value })}
+          />
+        </div>
+        <div className="form-group">
+          <label>最长检查间隔（分钟，电脑休眠唤醒后最迟这么久补发提醒）</label>
+          <input
+            type="number"
+            min={1}
+            max={60}
+            value={settings.poll_interval_minutes}
+            onChange={(e) => update({ poll_interval_minutes: Number(e.target.value) })}
+          />
+        </div>
+
+        <h3>💾 数据库位置</h3>
+        <div className="settings-row">
+          <span>{settings.database_path ?? '默认（应用数据目录）'}</span>
+          <button type="button" onClick={handleChooseDatabase}>
+            📂 选择…
+          </button>
+          {settings.database_path && (
+            <button type="button" onClick={() => update({ database_path: undefined })}>
+              恢复默认
+            </button>
+          )}
+        </div>
+        <p className="settings-hint">重启后生效。新位置没有数据库文件时会复制当前的数据库。</p>
+
+        <h3>📤 导出默认值</h3>
+        <div className="form-group">
+          <label>格式</label>
+          <select
+            value={settings.export_format}
+            onChange={(e) => update({ export_format: e.target.value as ExportFormat })}
+          >
+            <option value="html">HTML</option>
+            <option value="json">JSON（完整备份）</option>
+            <option value="csv">CSV</option>
+          </select>
+        </div>
+        <label>
+          <input
+            type="checkbox"
+            checked={settings.export_include_saved_searches}
+            onChange={(e) => update({ export_include_saved_searches: e.target.checked })}
+          />
+          HTML 导出包含智能文件夹
+        </label>
+        <div className="settings-columns">
+          {CSV_COLUMNS.map(([column, label]) => (
+            <label key={column}>
+              <input
+                type="checkbox"
+                checked={settings.export_columns.includes(column)}
+                onChange={(e) => toggleColumn(column, e.target.checked)}
+              />
+              {label}
+            </label>
+          ))}
+        </div>
+
+        <div className="form-actions">
+          <button onClick={handleSave}>💾 保存</button>
+          <button className="modal-close" onClick={onClose}>
+            关闭
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
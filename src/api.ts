@@ -1,5 +1,25 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { Bookmark, CreateBookmarkInput, UpdateBookmarkInput } from './types';
+import type {
+  Bookmark,
+  CreateBookmarkInput,
+  CsvColumn,
+  DeleteFolderMode,
+  DuplicateGroup,
+  ExportFormat,
+  Folder,
+  ImportFormat,
+  ImportPolicy,
+  ImportReport,
+  Reminder,
+  ReminderEvent,
+  ReminderStats,
+  SavedSearch,
+  SavedSearchInput,
+  SearchResult,
+  Settings,
+  Tag,
+  UpdateBookmarkInput,
+} from './types';
 
 export const bookmarkApi = {
   async createBookmark(input: CreateBookmarkInput): Promise<Bookmark> {
@@ -27,10 +47,74 @@ export const bookmarkApi = {
     return await invoke('delete_bookmark', { id });
   },
 
-  async searchBookmarks(query: string): Promise<Bookmark[]> {
+  async searchBookmarks(query: string): Promise<SearchResult[]> {
     return await invoke('search_bookmarks', { query });
   },
 
+  async findDuplicates(): Promise<DuplicateGroup[]> {
+    return await invoke('find_duplicates');
+  },
+
+  async mergeBookmarks(keepId: number, mergeIds: number[]): Promise<Bookmark> {
+    return await invoke('merge_bookmarks', { keepId, mergeIds });
+  },
+
+  async listTags(): Promise<Tag[]> {
+    return await invoke('list_tags');
+  },
+
+  async renameTag(oldName: string, newName: string): Promise<void> {
+    return await invoke('rename_tag', { oldName, newName });
+  },
+
+  async mergeTags(source: string, target: string): Promise<void> {
+    return await invoke('merge_tags', { source, target });
+  },
+
+  async deleteTag(name: string): Promise<void> {
+    return await invoke('delete_tag', { name });
+  },
+
+  async listFolders(): Promise<Folder[]> {
+    return await invoke('list_folders');
+  },
+
+  async createFolder(name: string, parentId?: number): Promise<Folder> {
+    return await invoke('create_folder', { name, parentId });
+  },
+
+  async renameFolder(id: number, name: string): Promise<Folder> {
+    return await invoke('rename_folder', { id, name });
+  },
+
+  async moveFolder(id: number, parentId?: number): Promise<Folder> {
+    return await invoke('move_folder', { id, parentId });
+  },
+
+  async deleteFolder(id: number, mode: DeleteFolderMode): Promise<void> {
+    return await invoke('delete_folder', { id, mode });
+  },
+
+  async listSavedSearches(): Promise<SavedSearch[]> {
+    return await invoke('list_saved_searches');
+  },
+
+  async createSavedSearch(input: SavedSearchInput): Promise<SavedSearch> {
+    return await invoke('create_saved_search', { input });
+  },
+
+  async updateSavedSearch(id: number, input: SavedSearchInput): Promise<SavedSearch> {
+    return await invoke('update_saved_search', { id, input });
+  },
+
+  async deleteSavedSearch(id: number): Promise<void> {
+    return await invoke('delete_saved_search', { id });
+  },
+
+  async evaluateSavedSearch(id: number): Promise<Bookmark[]> {
+    return await invoke('evaluate_saved_search', { id });
+  },
+
   async getBookmarksWithReminders(): Promise<Bookmark[]> {
     return await invoke('get_bookmarks_with_reminders');
   },
@@ -43,29 +127,88 @@ export const bookmarkApi = {
     return await invoke('record_visit', { bookmarkId });
   },
 
-  async markReminderCompleted(bookmarkId: number): Promise<void> {
-    return await invoke('mark_reminder_completed', { bookmarkId });
+  async listReminders(bookmarkId: number): Promise<Reminder[]> {
+    return await invoke('list_reminders', { bookmarkId });
+  },
+
+  async createReminder(bookmarkId: number, reminder: Reminder): Promise<Reminder> {
+    return await invoke('create_reminder', { bookmarkId, reminder });
+  },
+
+  async updateReminder(id: number, reminder: Reminder): Promise<Reminder> {
+    return await invoke('update_reminder', { id, reminder });
+  },
+
+  async deleteReminder(id: number): Promise<void> {
+    return await invoke('delete_reminder', { id });
   },
 
-  async snoozeReminder(bookmarkId: number, minutes: number): Promise<void> {
-    return await invoke('snooze_reminder', { bookmarkId, minutes });
+  async markReminderCompleted(reminderId: number): Promise<void> {
+    return await invoke('mark_reminder_completed', { reminderId });
   },
 
-  async importBookmarks(filePath: string): Promise<number> {
+  async snoozeReminder(reminderId: number, minutes: number): Promise<void> {
+    return await invoke('snooze_reminder', { reminderId, minutes });
+  },
+
+  async dismissReminder(reminderId: number): Promise<void> {
+    return await invoke('dismiss_reminder', { reminderId });
+  },
+
+  async getReminderHistory(bookmarkId?: number, limit?: number): Promise<ReminderEvent[]> {
+    return await invoke('get_reminder_history', { bookmarkId, limit });
+  },
+
+  async getReminderStats(bookmarkId?: number): Promise<ReminderStats[]> {
+    return await invoke('get_reminder_stats', { bookmarkId });
+  },
+
+  async previewReminderSchedule(reminder: Reminder, count?: number): Promise<string[]> {
+    return await invoke('preview_reminder_schedule', { reminder, count });
+  },
+
+  async importBookmarks(
+    filePath: string,
+    format?: ImportFormat,
+    policy: ImportPolicy = 'skip_existing',
+    dryRun = false,
+  ): Promise<ImportReport> {
     try {
-      const result = await invoke('import_bookmarks', { filePath });
-      return result as number;
+      const result = await invoke('import_bookmarks', { filePath, format, policy, dryRun });
+      return result as ImportReport;
+    } catch (error) {
+      throw error;
+    }
+  },
+
+  async cancelImport(): Promise<void> {
+    try {
+      await invoke('cancel_import');
     } catch (error) {
       throw error;
     }
   },
 
-  async exportBookmarks(filePath: string): Promise<number> {
+  // 不指定的选项使用设置中的导出默认值
+  async exportBookmarks(
+    filePath: string,
+    format?: ExportFormat,
+    columns?: CsvColumn[],
+    includeSavedSearches?: boolean,
+  ): Promise<number> {
     try {
-      const result = await invoke('export_bookmarks', { filePath });
+      const result = await invoke('export_bookmarks', { filePath, format, columns, includeSavedSearches });
       return result as number;
     } catch (error) {
       throw error;
     }
   },
+
+  async getSettings(): Promise<Settings> {
+    return await invoke('get_settings');
+  },
+
+  async updateSettings(settings: Settings): Promise<Settings> {
+    return await invoke('update_settings', { settings });
+  },
 };
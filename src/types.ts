@@ -2,11 +2,14 @@ export interface Bookmark {
   id?: number;
   title: string;
   url: string;
-  category?: string;
+  folder_id?: number;
+  folder_path?: string;
   tags: string[];
   icon_url?: string;
   notes?: string;
-  reminder?: Reminder;
+  keyword?: string;
+  reminders: Reminder[];
+  exclude_from_digest: boolean; // 开启提醒摘要时仍单独通知
   visit_count: number;
   last_visited?: string;
   created_at: string;
@@ -14,35 +17,226 @@ export interface Bookmark {
 }
 
 export interface Reminder {
+  id?: number; // 保存后由后端分配
   enabled: boolean;
   frequency: ReminderFrequency;
   time: string; // HH:MM format
   days: number[]; // 0-6 for Sunday-Saturday
   last_reminded?: string;
   next_reminder?: string;
+  catch_up?: CatchUpPolicy; // 默认 fire_once
+  timezone?: string; // IANA 时区名，未设置时后端使用系统时区
+  // 通知标题和内容模板，可用 {title} {url} {folder} {streak} {time}
+  title_template?: string;
+  message_template?: string;
 }
 
+// reminder-triggered 事件的内容
+export interface ReminderNotification {
+  bookmark: Bookmark;
+  reminder: Reminder; // 到期的那个提醒
+  title: string;
+  body: string;
+  actions: boolean; // 系统通知带操作按钮，已在后台处理
+}
+
+// reminder-digest 事件的内容：同时到期的多个提醒合并成的一条通知
+export interface ReminderDigest {
+  title: string;
+  body: string; // 每个提醒一行
+  items: ReminderNotification[];
+  actions: boolean; // 系统通知带操作按钮，已在后台处理
+}
+
+// 免打扰设置，时段按系统时区，结束早于开始时跨过午夜（如 22:00–07:00）
+export interface QuietHours {
+  windows: QuietWindow[];
+  policy: QuietHoursPolicy;
+  paused_until?: string; // 暂停所有提醒直到这个时间
+}
+
+export interface QuietWindow {
+  start: string; // HH:MM
+  end: string; // HH:MM
+}
+
+// 免打扰期间到期的提醒：推迟到结束时提醒，或者不提醒
+export type QuietHoursPolicy = 'defer' | 'drop';
+
+// 应用设置，修改后后端发送 settings-changed 事件
+export interface Settings {
+  poll_interval_minutes: number; // 提醒服务最长休眠时间，1–60 分钟
+  notification_language?: NotificationLanguage; // 不设置时跟随系统
+  // 提醒没有设置模板时使用的通知标题和内容
+  default_title_template?: string;
+  default_message_template?: string;
+  quiet_hours: QuietHours;
+  reminder_digest: boolean; // 同时到期的多个提醒合并成一条通知
+  database_path?: string; // 数据库文件的绝对路径，重启后生效；不设置时使用应用数据目录
+  export_format: ExportFormat;
+  export_columns: CsvColumn[];
+  export_include_saved_searches: boolean;
+}
+
+export type NotificationLanguage = 'chinese' | 'english';
+
+// 错过提醒时间后的处理：补发一次、不补发、每错过一次补发一次
+export type CatchUpPolicy = 'fire_once' | 'skip' | 'fire_all';
+
 export type ReminderFrequency =
   | { type: 'daily' }
   | { type: 'weekly' }
   | { type: 'custom'; interval_days: number }
-  | { type: 'once' };
+  | { type: 'once' }
+  | { type: 'rrule'; rule: string }; // RFC 5545 RRULE，例如 FREQ=MONTHLY;BYDAY=1MO
+
+export type ReminderEventKind = 'fired' | 'completed' | 'snoozed' | 'dismissed' | 'skipped';
+
+export interface ReminderEvent {
+  id: number;
+  bookmark_id: number;
+  reminder_id?: number;
+  bookmark_title: string;
+  kind: ReminderEventKind;
+  scheduled_for?: string; // fired 为原定时间，snoozed 为推迟到的时间
+  created_at: string;
+}
+
+export interface ReminderStats {
+  reminder_id: number;
+  bookmark_id: number;
+  fired: number;
+  completed: number;
+  snoozed: number;
+  dismissed: number;
+  skipped: number;
+  current_streak: number;
+  longest_streak: number;
+  completion_rate: number; // 0-1
+  last_completed?: string;
+}
+
+export interface SearchResult {
+  bookmark: Bookmark;
+  snippet?: string; // 关键词用 <mark> 标记
+  score: number;
+}
+
+export interface SavedSearch {
+  id: number;
+  name: string;
+  query: string;
+  folder_id?: number;
+  tags: string[];
+  min_visits?: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface SavedSearchInput {
+  name: string;
+  query: string;
+  folder_id?: number;
+  tags: string[];
+  min_visits?: number;
+}
+
+export interface DuplicateGroup {
+  canonical_url: string;
+  bookmarks: Bookmark[];
+}
+
+export type ImportFormat =
+  | 'html'
+  | 'firefox_places'
+  | 'chromium'
+  | 'json'
+  | 'csv'
+  | 'pinboard'
+  | 'pocket'
+  | 'raindrop';
+
+export type ExportFormat = 'html' | 'json' | 'csv';
+
+export type CsvColumn =
+  | 'title'
+  | 'url'
+  | 'folder'
+  | 'tags'
+  | 'notes'
+  | 'keyword'
+  | 'icon_url'
+  | 'visit_count'
+  | 'last_visited'
+  | 'created_at'
+  | 'updated_at'
+  | 'reminder';
+
+export type ImportPolicy = 'skip_existing' | 'update_existing' | 'always_create';
+
+export interface ImportFailure {
+  title: string;
+  url: string;
+  reason: string;
+}
+
+export interface ImportReport {
+  created: number;
+  updated: number;
+  skipped: number;
+  failed: ImportFailure[];
+  dry_run: boolean;
+  cancelled: boolean;
+  error?: string; // 中途出错的原因，出错前已提交的批次会保留
+}
+
+export interface ImportProgress {
+  processed: number;
+  created: number;
+  updated: number;
+  skipped: number;
+  failed: number;
+  total?: number; // 边读边解析时未知
+  bytes_read: number;
+  total_bytes: number;
+}
+
+export interface Tag {
+  id: number;
+  name: string;
+  bookmark_count: number;
+}
+
+export interface Folder {
+  id: number;
+  name: string;
+  parent_id?: number;
+  path: string;
+  bookmark_count: number;
+  created_at: string;
+}
+
+export type DeleteFolderMode = 'cascade' | 'reparent';
 
 export interface CreateBookmarkInput {
   title: string;
   url: string;
-  category?: string;
+  folder_id?: number;
   tags: string[];
+  icon_url?: string;
   notes?: string;
-  reminder?: Reminder;
+  keyword?: string;
+  reminders: Reminder[];
+  exclude_from_digest?: boolean;
 }
 
 export interface UpdateBookmarkInput {
   id: number;
   title?: string;
   url?: string;
-  category?: string;
+  folder_id?: number;
   tags?: string[];
   notes?: string;
-  reminder?: Reminder;
+  reminders?: Reminder[]; // 替换全部提醒，带 id 的沿用原有记录；不传时不修改
+  exclude_from_digest?: boolean;
 }